@@ -128,6 +128,43 @@ impl TopographicMap {
 
         trail_heads
     }
+
+    /// Computes the total trailhead score and rating together in a single depth-first traversal
+    /// per trailhead: every path reaching a 9 adds to the rating, every distinct 9 to the score.
+    #[cfg(test)]
+    fn scores_and_ratings(&self) -> (u16, u16) {
+        let (mut score, mut rating) = (0, 0);
+        let mut stack = Vec::new();
+        let mut peaks = HashSet::new();
+        for row in self.map.iter() {
+            for (trail_start, &e) in row {
+                if e != 0 {
+                    continue;
+                }
+
+                stack.push(to_signed_coordinate!(trail_start));
+                while let Some(cord) = stack.pop() {
+                    let curr_num = *self.map.get(&cord).unwrap();
+                    if curr_num == 9 {
+                        rating += 1;
+                        peaks.insert(cord);
+                        continue;
+                    }
+
+                    Direction::direction_list()
+                        .map(|dir| cord + dir)
+                        .into_iter()
+                        .filter(|next| self.map.get(next).is_some_and(|&n| n == curr_num + 1))
+                        .for_each(|next| stack.push(next));
+                }
+
+                score += peaks.len() as u16;
+                peaks.clear();
+            }
+        }
+
+        (score, rating)
+    }
 }
 
 impl From<Vec<String>> for TopographicMap {
@@ -143,3 +180,25 @@ impl From<Vec<String>> for TopographicMap {
         Self { map }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_scores_and_ratings() {
+        let map = TopographicMap::from(
+            [
+                "89010123", "78121874", "87430965", "96549874", "45678903", "32019012", "01329801",
+                "10456732",
+            ]
+            .map(String::from)
+            .to_vec(),
+        );
+        assert_eq!(map.scores_and_ratings(), (36, 81));
+        assert_eq!(
+            map.scores_and_ratings(),
+            (map.count_trail_heads(), map.count_rating())
+        );
+    }
+}
@@ -15,11 +15,11 @@ pub fn run() {
 }
 
 fn part1(stones: Stones) -> u64 {
-    stones.blink_n_times(25)
+    stones.count_after(25)
 }
 
 fn part2(stones: Stones) -> u64 {
-    stones.blink_n_times(75)
+    stones.count_after(75)
 }
 
 #[derive(Debug)]
@@ -34,15 +34,68 @@ enum NextDigit {
 }
 
 impl Stones {
-    fn blink_n_times(self, blink_times: u8) -> u64 {
+    /// Counts the stones present after blinking `blinks` times
+    fn count_after(&self, blinks: u8) -> u64 {
         // (stone, blink_times) -> result
         let mut cache: HashMap<(u64, u8), u64> = HashMap::new();
         self.stones
-            .into_iter()
-            .map(|stone| Self::sim(&mut cache, stone as u64, blink_times))
+            .iter()
+            .map(|&stone| Self::sim(&mut cache, stone as u64, blinks))
             .sum()
     }
 
+    /// Counts the stones present after blinking `blinks` times by tracking how many stones
+    /// share each value and transforming the whole layer at once.
+    /// Memory is bounded by the number of distinct stone values rather than the recursion cache.
+    #[cfg(test)]
+    fn count_layers(&self, blinks: u8) -> u64 {
+        let mut layer: HashMap<u64, u64> = HashMap::new();
+        for &stone in &self.stones {
+            *layer.entry(stone as u64).or_insert(0) += 1;
+        }
+
+        for _ in 0..blinks {
+            let mut next_layer = HashMap::with_capacity(layer.len());
+            for (stone, count) in layer {
+                match Self::next_digit(stone) {
+                    NextDigit::Single(n) => *next_layer.entry(n).or_insert(0) += count,
+                    NextDigit::Double(left, right) => {
+                        *next_layer.entry(left).or_insert(0) += count;
+                        *next_layer.entry(right).or_insert(0) += count;
+                    }
+                }
+            }
+            layer = next_layer;
+        }
+
+        layer.values().sum()
+    }
+
+    /// Counts how many unique stone values appear at any point within `blinks` blinks.
+    /// This bounds the number of distinct stones the memoization in `sim` has to deal with.
+    #[cfg(test)]
+    fn distinct_stone_values(&self, blinks: u8) -> usize {
+        let mut layer = self
+            .stones
+            .iter()
+            .map(|&stone| stone as u64)
+            .collect::<std::collections::HashSet<_>>();
+        let mut seen = layer.clone();
+
+        for _ in 0..blinks {
+            layer = layer
+                .into_iter()
+                .flat_map(|stone| match Self::next_digit(stone) {
+                    NextDigit::Single(n) => vec![n],
+                    NextDigit::Double(left, right) => vec![left, right],
+                })
+                .collect();
+            seen.extend(&layer);
+        }
+
+        seen.len()
+    }
+
     fn next_digit(n: u64) -> NextDigit {
         if n == 0 {
             NextDigit::Single(1)
@@ -100,3 +153,35 @@ impl From<Vec<String>> for Stones {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Stones {
+        Stones::from(vec!["125 17".into()])
+    }
+
+    #[test]
+    fn layers_match_recursion() {
+        let stones = sample();
+        for blinks in [0, 1, 6, 25, 75] {
+            assert_eq!(stones.count_layers(blinks), stones.count_after(blinks));
+        }
+    }
+
+    #[test]
+    fn counts_after_blinks() {
+        let stones = sample();
+        assert_eq!(stones.count_after(6), 22);
+        assert_eq!(stones.count_after(25), 55312);
+        assert_eq!(stones.count_after(75), 65601038650482);
+    }
+
+    #[test]
+    fn counts_distinct_values() {
+        // 0 -> 1 -> 2024 -> 20 24
+        let stones = Stones::from(vec!["0".into()]);
+        assert_eq!(stones.distinct_stone_values(3), 5);
+    }
+}
@@ -54,6 +54,17 @@ impl Garden {
         price
     }
 
+    /// Counts the number of sides (straight fence runs) of the region containing `start`.
+    /// A region has as many sides as it has corners.
+    #[cfg(test)]
+    fn count_sides(&self, start: Coordinate<isize>) -> u32 {
+        let plot = *self
+            .garden
+            .get(&start)
+            .expect("Start must be within the garden");
+        self.calculate_price_with_sides(start, plot, &mut HashSet::new())
+    }
+
     fn calculate_price(
         &self,
         curr: Coordinate<isize>,
@@ -168,3 +179,38 @@ impl From<Vec<String>> for Garden {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn garden(input: &str) -> Garden {
+        Garden::from(input.lines().map(String::from).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn counts_sides_of_example_regions() {
+        let small = garden("AAAA\nBBCD\nBBCC\nEEEC");
+        for ((i, j), sides) in [
+            ((0, 0), 4),
+            ((1, 0), 4),
+            ((1, 2), 8),
+            ((1, 3), 4),
+            ((3, 0), 4),
+        ] {
+            assert_eq!(small.count_sides(Coordinate::new(i, j)), sides);
+        }
+        assert_eq!(small.fencing_price(true), 80);
+
+        let e_shape = garden("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE");
+        assert_eq!(e_shape.count_sides(Coordinate::new(0, 0)), 12);
+        assert_eq!(e_shape.count_sides(Coordinate::new(1, 1)), 4);
+        assert_eq!(e_shape.fencing_price(true), 236);
+
+        // The inner B regions touch the outer A region only at a corner
+        let enclosed = garden("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA");
+        assert_eq!(enclosed.count_sides(Coordinate::new(0, 0)), 12);
+        assert_eq!(enclosed.count_sides(Coordinate::new(1, 3)), 4);
+        assert_eq!(enclosed.fencing_price(true), 368);
+    }
+}
@@ -87,29 +87,91 @@ impl Machine {
     ///
     /// An `Option` containing the minimum number of tickets required to reach the prize, or `None` if it is not possible.
     fn minimum_tickets_linear_algebra(&self) -> Option<i64> {
-        let det = self.determinant(self.button_a, self.button_b);
-        if det != 0 {
-            // There's at least a solution
-            let x = self.determinant(self.prize, self.button_b);
-            let dx = x / det;
-
-            let y = self.determinant(self.button_a, self.prize);
-            let dy = y / det;
-
-            let a = self.button_a.0 * dx + self.button_b.0 * dy;
-            let b = self.button_a.1 * dx + self.button_b.1 * dy;
-            if (a, b) == self.prize {
-                return Some(dx * Machine::BUTTON_A_MULTIPLIER + dy * Machine::BUTTON_B_MULTIPLIER);
-            }
+        // Computed in i128 as the products of the prize (up to 1e13) and the buttons can overflow i64
+        let det = Self::determinant(self.button_a, self.button_b);
+        if det == 0 {
+            // The buttons move the claw along the same line
+            return self.minimum_tickets_collinear();
+        }
+
+        // There's at least a solution
+        let x = Self::determinant(self.prize, self.button_b);
+        let dx = x / det;
+
+        let y = Self::determinant(self.button_a, self.prize);
+        let dy = y / det;
+
+        let a = self.button_a.0 as i128 * dx + self.button_b.0 as i128 * dy;
+        let b = self.button_a.1 as i128 * dx + self.button_b.1 as i128 * dy;
+        if (a, b) == (self.prize.0 as i128, self.prize.1 as i128) {
+            let cost = dx * Machine::BUTTON_A_MULTIPLIER as i128
+                + dy * Machine::BUTTON_B_MULTIPLIER as i128;
+            return i64::try_from(cost).ok();
         }
         None
     }
 
-    fn determinant(&self, a: (i64, i64), b: (i64, i64)) -> i64 {
-        let (ax, ay) = a;
-        let (bx, by) = b;
+    /// Finds the minimum number of tickets required to reach the prize when both buttons move
+    /// the claw along the same line, where many press combinations can reach the prize.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the minimum number of tickets required to reach the prize, or `None` if it is not possible.
+    fn minimum_tickets_collinear(&self) -> Option<i64> {
+        // The prize has to lie on the line the buttons move along
+        if Self::determinant(self.button_a, self.prize) != 0
+            || Self::determinant(self.button_b, self.prize) != 0
+        {
+            return None;
+        }
+
+        // Everything lies on one line so a single axis is enough, as long as the line isn't
+        // perpendicular to it
+        let use_x = self.button_a.0 != 0 || self.button_b.0 != 0;
+        let axis = |(x, y): (i64, i64)| if use_x { x } else { y };
+        let (a, b, prize) = (axis(self.button_a), axis(self.button_b), axis(self.prize));
+
+        // Order the buttons as (cheap, pricey) by their cost per unit moved
+        let ((cheap, cheap_cost), (pricey, pricey_cost)) =
+            if Self::BUTTON_A_MULTIPLIER * b <= Self::BUTTON_B_MULTIPLIER * a {
+                (
+                    (a, Self::BUTTON_A_MULTIPLIER),
+                    (b, Self::BUTTON_B_MULTIPLIER),
+                )
+            } else {
+                (
+                    (b, Self::BUTTON_B_MULTIPLIER),
+                    (a, Self::BUTTON_A_MULTIPLIER),
+                )
+            };
+
+        if cheap == 0 {
+            // Neither button moves the claw, so only a prize at the origin can be won. The whole
+            // prize is checked as the projection drops the other axis
+            return (self.prize == (0, 0)).then_some(0);
+        }
 
-        (ax * by) - (ay * bx)
+        // Every `cheap / gcd` presses of the pricey button can be swapped for presses of the cheap
+        // one for less, so the optimum presses the pricey button fewer times than that.
+        // Cost only grows with each pricey press so the first combination that works is the cheapest.
+        let max_pricey_presses = if pricey == 0 {
+            0
+        } else {
+            let (mut x, mut y) = (cheap, pricey);
+            while y != 0 {
+                (x, y) = (y, x % y);
+            }
+            (cheap / x - 1).min(prize / pricey)
+        };
+
+        (0..=max_pricey_presses).find_map(|pricey_presses| {
+            let rest = prize - pricey_presses * pricey;
+            (rest % cheap == 0).then(|| pricey_presses * pricey_cost + (rest / cheap) * cheap_cost)
+        })
+    }
+
+    fn determinant((ax, ay): (i64, i64), (bx, by): (i64, i64)) -> i128 {
+        (ax as i128 * by as i128) - (ay as i128 * bx as i128)
     }
 
     fn increase_price(&mut self, extra: i64) {
@@ -147,10 +209,16 @@ impl ClawMachines {
     }
 
     fn find_min_ticket_costs_like_a_math_student(&self) -> i64 {
+        self.per_machine_costs().into_iter().flatten().sum()
+    }
+
+    /// Returns the minimum ticket cost of each machine in input order,
+    /// or `None` for machines whose prize can't be reached.
+    fn per_machine_costs(&self) -> Vec<Option<i64>> {
         self.machines
             .iter()
-            .filter_map(Machine::minimum_tickets_linear_algebra)
-            .sum()
+            .map(Machine::minimum_tickets_linear_algebra)
+            .collect()
     }
 
     fn increase_price(mut self, extra: i64) -> Self {
@@ -210,3 +278,103 @@ impl From<Vec<String>> for ClawMachines {
         ClawMachines { machines }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn machine(button_a: (i64, i64), button_b: (i64, i64), prize: (i64, i64)) -> Machine {
+        Machine {
+            button_a,
+            button_b,
+            prize,
+        }
+    }
+
+    #[test]
+    fn collinear_buttons_reach_prize() {
+        // A moves 4 for 3 tickets and B moves 1 for 1 ticket, so two of each beats ten B presses
+        let parallel = machine((4, 4), (1, 1), (10, 10));
+        assert_eq!(parallel.minimum_tickets_linear_algebra(), Some(8));
+
+        // B is cheaper per unit but only gets to 7 with the help of two A presses
+        let parallel = machine((2, 4), (3, 6), (7, 14));
+        assert_eq!(parallel.minimum_tickets_linear_algebra(), Some(7));
+    }
+
+    #[test]
+    fn collinear_buttons_miss_prize() {
+        // Both buttons only move an even distance
+        let parallel = machine((2, 4), (4, 8), (7, 14));
+        assert_eq!(parallel.minimum_tickets_linear_algebra(), None);
+
+        // The prize is off the line the buttons move along
+        let parallel = machine((2, 4), (3, 6), (7, 15));
+        assert_eq!(parallel.minimum_tickets_linear_algebra(), None);
+
+        // Buttons that don't move the claw can only win a prize at the origin
+        let stuck = machine((0, 0), (0, 0), (5, 0));
+        assert_eq!(stuck.minimum_tickets_linear_algebra(), None);
+        let stuck = machine((0, 0), (0, 0), (0, 0));
+        assert_eq!(stuck.minimum_tickets_linear_algebra(), Some(0));
+    }
+
+    const SAMPLE: &str = "\
+Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176
+
+Button A: X+17, Y+86
+Button B: X+84, Y+37
+Prize: X=7870, Y=6450
+
+Button A: X+69, Y+23
+Button B: X+27, Y+71
+Prize: X=18641, Y=10279";
+
+    fn sample() -> ClawMachines {
+        ClawMachines::from(SAMPLE.lines().map(String::from).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn per_machine_costs_sum_to_total() {
+        let claw_machines = sample();
+        let costs = claw_machines.per_machine_costs();
+        assert_eq!(costs, [Some(280), None, Some(200), None]);
+        assert_eq!(
+            costs.into_iter().flatten().sum::<i64>(),
+            claw_machines.find_min_ticket_costs_like_a_comp_sci_student()
+        );
+        assert_eq!(
+            claw_machines.find_min_ticket_costs_like_a_math_student(),
+            480
+        );
+    }
+
+    #[test]
+    fn solves_prizes_past_i64_products() {
+        // 10^16 presses of A and 3 * 10^16 presses of B, where prize * button overflows an i64
+        let far = machine(
+            (97, 13),
+            (11, 89),
+            (1_300_000_000_000_000_000, 2_800_000_000_000_000_000),
+        );
+        assert!((far.prize.0 as i128 * far.button_b.1 as i128) > i64::MAX as i128);
+        assert_eq!(
+            far.minimum_tickets_linear_algebra(),
+            Some(60_000_000_000_000_000)
+        );
+
+        // The sample machines after the part 2 unit conversion
+        assert_eq!(
+            sample()
+                .increase_price(10000000000000)
+                .find_min_ticket_costs_like_a_math_student(),
+            875318608908
+        );
+    }
+}
@@ -104,6 +104,12 @@ impl<const WIDE: u32, const TALL: u32> RobotSimulation<WIDE, TALL> {
     }
 
     fn determine_safety_factor(&self) -> u32 {
+        self.quadrant_counts().into_iter().product()
+    }
+
+    /// Counts the robots in each quadrant, in Q1–Q4 order.
+    /// Robots sitting exactly on the middle row or column belong to no quadrant.
+    fn quadrant_counts(&self) -> [u32; 4] {
         let (wide, tall) = (WIDE, TALL);
         // Example (wide, tall) => (11, 7)
         // Split into quadrants
@@ -123,17 +129,14 @@ impl<const WIDE: u32, const TALL: u32> RobotSimulation<WIDE, TALL> {
             (tall as i32 / 2) + 1..tall as i32,
         );
 
-        [q1, q2, q3, q4]
-            .into_iter()
-            .map(|(quad_wide, quad_tall)| {
-                self.robot
-                    .iter()
-                    .filter(|robot| {
-                        quad_wide.contains(&robot.pos.i) && quad_tall.contains(&robot.pos.j)
-                    })
-                    .count() as u32
-            })
-            .fold(1, |acc, x| x * acc)
+        [q1, q2, q3, q4].map(|(quad_wide, quad_tall)| {
+            self.robot
+                .iter()
+                .filter(|robot| {
+                    quad_wide.contains(&robot.pos.i) && quad_tall.contains(&robot.pos.j)
+                })
+                .count() as u32
+        })
     }
 }
 
@@ -153,3 +156,34 @@ impl<const WIDE: u32, const TALL: u32> From<Vec<String>> for RobotSimulation<WID
         RobotSimulation { robot: robots }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simulation(robots: &[&str]) -> RobotSimulation<11, 7> {
+        RobotSimulation::from(
+            robots
+                .iter()
+                .map(|&robot| String::from(robot))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn counts_robots_per_quadrant() {
+        let placed = simulation(&[
+            "p=0,0 v=0,0",  // Q1
+            "p=10,0 v=0,0", // Q2
+            "p=0,6 v=0,0",  // Q3
+            "p=10,6 v=0,0", // Q4
+            "p=6,4 v=0,0",  // Q4
+            "p=5,0 v=0,0",  // Middle column
+            "p=0,3 v=0,0",  // Middle row
+            "p=5,3 v=0,0",  // Centre
+        ]);
+
+        assert_eq!(placed.quadrant_counts(), [1, 1, 1, 2]);
+        assert_eq!(placed.determine_safety_factor(), 2);
+    }
+}
@@ -4,6 +4,7 @@ use aoc_utils_rust::day_setup::Utils;
 use aoc_utils_rust::grid::unsized_grid::UnsizedGrid;
 use aoc_utils_rust::grid::{Grid, GridMut};
 use std::collections::HashSet;
+use std::fmt;
 use std::fmt::Debug;
 use std::mem;
 
@@ -53,20 +54,26 @@ impl WarehouseRobot<ObjectMalfunctioning> {
     }
 
     fn sum_of_gps_coordinates(&self) -> u32 {
+        self.box_gps_coordinates().into_iter().sum()
+    }
+
+    /// GPS coordinate of every box in reading order, measured from the box's left edge
+    fn box_gps_coordinates(&self) -> Vec<u32> {
         const MULTIPLIER: u32 = 100;
         self.map
             .iter()
-            .map(|row| {
-                row.map(|(coord, obj)| match *obj {
-                    ObjectMalfunctioning::BoxLeft => MULTIPLIER * coord.i as u32 + coord.j as u32,
+            .flat_map(|row| {
+                row.filter_map(|(coord, obj)| match *obj {
+                    ObjectMalfunctioning::BoxLeft => {
+                        Some(MULTIPLIER * coord.i as u32 + coord.j as u32)
+                    }
                     ObjectMalfunctioning::Wall
                     | ObjectMalfunctioning::Empty
                     | ObjectMalfunctioning::Robot
-                    | ObjectMalfunctioning::BoxRight => 0,
+                    | ObjectMalfunctioning::BoxRight => None,
                 })
-                .sum::<u32>()
             })
-            .sum()
+            .collect()
     }
 
     fn move_vertically(&mut self, (times, dir): Dir) {
@@ -264,17 +271,21 @@ impl WarehouseRobot<ObjectNormal> {
     }
 
     fn sum_of_gps_coordinates(&self) -> u32 {
+        self.box_gps_coordinates().into_iter().sum()
+    }
+
+    /// GPS coordinate of every box in reading order
+    fn box_gps_coordinates(&self) -> Vec<u32> {
         const MULTIPLIER: u32 = 100;
         self.map
             .iter()
-            .map(|row| {
-                row.map(|(coord, obj)| match *obj {
-                    ObjectNormal::Box => MULTIPLIER * coord.i as u32 + coord.j as u32,
-                    ObjectNormal::Wall | ObjectNormal::Empty | ObjectNormal::Robot => 0,
+            .flat_map(|row| {
+                row.filter_map(|(coord, obj)| match *obj {
+                    ObjectNormal::Box => Some(MULTIPLIER * coord.i as u32 + coord.j as u32),
+                    ObjectNormal::Wall | ObjectNormal::Empty | ObjectNormal::Robot => None,
                 })
-                .sum::<u32>()
             })
-            .sum()
+            .collect()
     }
 
     fn move_box(&mut self, (times, dir): Dir) {
@@ -347,34 +358,43 @@ impl Debug for ObjectMalfunctioning {
 }
 
 impl<T> WarehouseRobot<T> {
-    fn get_dir(e: char) -> (u8, Direction) {
-        (
+    fn get_dir(e: char) -> Option<(u8, Direction)> {
+        Some((
             1,
             match e {
                 '^' => Direction::North,
                 'v' => Direction::South,
                 '>' => Direction::East,
                 '<' => Direction::West,
-                _ => unreachable!(),
+                _ => return None,
             },
-        )
+        ))
     }
 
-    fn process_input(input: &[String]) -> (Vec<&str>, Vec<Dir>) {
-        let mut iter = input.into_iter();
+    fn process_input(input: &[String]) -> Result<(Vec<&str>, Vec<Dir>), WarehouseParseError> {
+        let mut iter = input.iter();
         let mut map: Vec<&str> = vec![];
         loop {
-            let line = iter.next().unwrap();
+            let line = iter.next().ok_or(WarehouseParseError::MissingSeparator)?;
             if line.is_empty() {
                 break;
             }
             map.push(line);
         }
 
+        let width = map
+            .first()
+            .ok_or(WarehouseParseError::EmptyMap)?
+            .chars()
+            .count();
+        if let Some(row) = map.iter().position(|row| row.chars().count() != width) {
+            return Err(WarehouseParseError::RaggedRow(row));
+        }
+
         let mut moves = vec![];
-        for line in iter {
-            line.chars().for_each(|e| {
-                let (c, d) = Self::get_dir(e);
+        for (i, line) in iter.enumerate() {
+            for (j, e) in line.chars().enumerate() {
+                let (c, d) = Self::get_dir(e).ok_or(WarehouseParseError::InvalidMove(e, (i, j)))?;
                 match moves.last_mut() {
                     Some((count, dir)) => {
                         // Group similar directions together
@@ -386,23 +406,73 @@ impl<T> WarehouseRobot<T> {
                     }
                     None => moves.push((c, d)),
                 }
-            });
+            }
         }
 
-        (map, moves)
+        Ok((map, moves))
     }
 }
 
-impl From<Vec<String>> for WarehouseRobot<ObjectMalfunctioning> {
-    fn from(line: Vec<String>) -> Self {
-        let (map, moves) = Self::process_input(&line);
+/// Errors found while parsing an untrusted warehouse map
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum WarehouseParseError {
+    /// (first robot, second robot) positions as `(row, col)` in the input map
+    MultipleRobots((usize, usize), (usize, usize)),
+    MissingRobot,
+    /// The offending character and its `(row, col)` position in the input map
+    InvalidGlyph(char, (usize, usize)),
+    /// No blank line separates the map from the moves
+    MissingSeparator,
+    EmptyMap,
+    /// Row of the input map whose width differs from the first row's
+    RaggedRow(usize),
+    /// The offending character and its `(line, col)` position in the moves
+    InvalidMove(char, (usize, usize)),
+}
+
+impl fmt::Display for WarehouseParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WarehouseParseError::MultipleRobots(first, second) => write!(
+                f,
+                "Multiple robots found in the map at {:?} and {:?}",
+                first, second
+            ),
+            WarehouseParseError::MissingRobot => write!(f, "No robot found in the map"),
+            WarehouseParseError::InvalidGlyph(c, pos) => {
+                write!(f, "Invalid character `{}` in the map at {:?}", c, pos)
+            }
+            WarehouseParseError::MissingSeparator => {
+                write!(f, "No blank line between the map and the moves")
+            }
+            WarehouseParseError::EmptyMap => write!(f, "The map has no rows"),
+            WarehouseParseError::RaggedRow(row) => {
+                write!(
+                    f,
+                    "Row {} of the map has a different width to the first row",
+                    row
+                )
+            }
+            WarehouseParseError::InvalidMove(c, pos) => {
+                write!(f, "Invalid move `{}` in the moves at {:?}", c, pos)
+            }
+        }
+    }
+}
+
+// Implemented for slices as `TryFrom<Vec<String>>` would conflict with the blanket impl over `From`
+impl TryFrom<&[String]> for WarehouseRobot<ObjectMalfunctioning> {
+    type Error = WarehouseParseError;
+
+    fn try_from(line: &[String]) -> Result<Self, Self::Error> {
+        let (map, moves) = Self::process_input(line)?;
 
         let mut grid = UnsizedGrid::new(
             map.len(),
-            map[0].len() * 2, /* Cuz its double the size */
+            map[0].chars().count() * 2, /* Cuz its double the size */
             ObjectMalfunctioning::Empty,
         );
-        let mut robot_pos = None;
+        let mut robot_pos: Option<Coordinate<usize>> = None;
         let next = Direction::East;
         for (i, row) in map.into_iter().enumerate() {
             let mut j = 0;
@@ -410,8 +480,11 @@ impl From<Vec<String>> for WarehouseRobot<ObjectMalfunctioning> {
                 let coord = Coordinate::new(i, j);
                 match c {
                     '@' => {
-                        if robot_pos.is_some() {
-                            panic!("Multiple robots found in the map");
+                        if let Some(first) = robot_pos {
+                            return Err(WarehouseParseError::MultipleRobots(
+                                (first.i, first.j / 2),
+                                (i, j / 2),
+                            ));
                         }
                         robot_pos = Some(coord);
                         // @.
@@ -431,7 +504,7 @@ impl From<Vec<String>> for WarehouseRobot<ObjectMalfunctioning> {
                                 ObjectMalfunctioning::BoxLeft,
                                 ObjectMalfunctioning::BoxRight,
                             ],
-                            _ => unreachable!(),
+                            _ => return Err(WarehouseParseError::InvalidGlyph(c, (i, j / 2))),
                         };
                         *grid.get_mut(&coord.into()).unwrap() = new[0];
                         *grid
@@ -446,27 +519,39 @@ impl From<Vec<String>> for WarehouseRobot<ObjectMalfunctioning> {
             }
         }
 
-        Self {
+        Ok(Self {
             map: grid,
-            robot_pos: robot_pos.unwrap().into(),
+            robot_pos: robot_pos.ok_or(WarehouseParseError::MissingRobot)?.into(),
             moves,
-        }
+        })
     }
 }
 
-impl From<Vec<String>> for WarehouseRobot<ObjectNormal> {
-    fn from(lines: Vec<String>) -> Self {
-        let (map, moves) = Self::process_input(&lines);
+impl From<Vec<String>> for WarehouseRobot<ObjectMalfunctioning> {
+    fn from(line: Vec<String>) -> Self {
+        Self::try_from(line.as_slice()).unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+// Implemented for slices as `TryFrom<Vec<String>>` would conflict with the blanket impl over `From`
+impl TryFrom<&[String]> for WarehouseRobot<ObjectNormal> {
+    type Error = WarehouseParseError;
 
-        let mut grid = UnsizedGrid::new(map.len(), map[0].len(), ObjectNormal::Empty);
-        let mut robot_pos = None;
+    fn try_from(lines: &[String]) -> Result<Self, Self::Error> {
+        let (map, moves) = Self::process_input(lines)?;
+
+        let mut grid = UnsizedGrid::new(map.len(), map[0].chars().count(), ObjectNormal::Empty);
+        let mut robot_pos: Option<Coordinate<isize>> = None;
         for (i, e) in map.into_iter().enumerate() {
             for (j, c) in e.chars().enumerate() {
                 let coord = Coordinate::new(i as isize, j as isize);
                 match c {
                     '@' => {
-                        if robot_pos.is_some() {
-                            panic!("Multiple robots found in the map");
+                        if let Some(first) = robot_pos {
+                            return Err(WarehouseParseError::MultipleRobots(
+                                (first.i as usize, first.j as usize),
+                                (i, j),
+                            ));
                         }
                         robot_pos = Some(coord);
                         *grid.get_mut(&coord).unwrap() = ObjectNormal::Robot;
@@ -476,17 +561,112 @@ impl From<Vec<String>> for WarehouseRobot<ObjectNormal> {
                             '#' => ObjectNormal::Wall,
                             '.' => ObjectNormal::Empty,
                             'O' => ObjectNormal::Box,
-                            _ => unreachable!(),
+                            _ => return Err(WarehouseParseError::InvalidGlyph(c, (i, j))),
                         }
                     }
                 }
             }
         }
 
-        Self {
+        Ok(Self {
             map: grid,
-            robot_pos: robot_pos.expect("No robot found in the map"),
+            robot_pos: robot_pos.ok_or(WarehouseParseError::MissingRobot)?,
             moves,
-        }
+        })
+    }
+}
+
+impl From<Vec<String>> for WarehouseRobot<ObjectNormal> {
+    fn from(lines: Vec<String>) -> Self {
+        Self::try_from(lines.as_slice()).unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(input: &str) -> Vec<String> {
+        input.lines().map(String::from).collect()
+    }
+
+    fn parse_error(input: &str) -> WarehouseParseError {
+        WarehouseRobot::<ObjectNormal>::try_from(lines(input).as_slice()).unwrap_err()
+    }
+
+    const SMALL_SAMPLE: &str = "\
+########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv<v>>v<<";
+
+    #[test]
+    fn rejects_two_robots() {
+        assert_eq!(
+            parse_error("#####\n#@.@#\n#####\n\n<>"),
+            WarehouseParseError::MultipleRobots((1, 1), (1, 3))
+        );
+        // Positions are reported in the input map rather than the widened one
+        assert_eq!(
+            WarehouseRobot::<ObjectMalfunctioning>::try_from(
+                lines("#####\n#@.@#\n#####\n\n<>").as_slice()
+            )
+            .unwrap_err(),
+            WarehouseParseError::MultipleRobots((1, 1), (1, 3))
+        );
+    }
+
+    #[test]
+    fn rejects_missing_robot() {
+        assert_eq!(
+            parse_error("#####\n#.O.#\n#####\n\n<>"),
+            WarehouseParseError::MissingRobot
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_glyph() {
+        assert_eq!(
+            parse_error("#####\n#@X.#\n#####\n\n<>"),
+            WarehouseParseError::InvalidGlyph('X', (1, 2))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_layout() {
+        assert_eq!(
+            parse_error("#####\n#@..#\n#####"),
+            WarehouseParseError::MissingSeparator
+        );
+        assert_eq!(parse_error("\n<>"), WarehouseParseError::EmptyMap);
+        assert_eq!(
+            parse_error("#####\n#@..#\n###\n\n<>"),
+            WarehouseParseError::RaggedRow(2)
+        );
+        assert_eq!(
+            parse_error("#####\n#@..#\n#####\n\n<>\n^x"),
+            WarehouseParseError::InvalidMove('x', (1, 1))
+        );
+    }
+
+    #[test]
+    fn box_gps_coordinates_sum_to_total() {
+        let mut warehouse = WarehouseRobot::<ObjectNormal>::from(lines(SMALL_SAMPLE));
+        warehouse.start_simulation();
+
+        let coordinates = warehouse.box_gps_coordinates();
+        assert_eq!(coordinates, [105, 106, 306, 403, 504, 604]);
+        assert_eq!(coordinates.iter().sum::<u32>(), 2028);
+        assert_eq!(warehouse.sum_of_gps_coordinates(), 2028);
+
+        // Wide boxes are measured from their left edge
+        let wide = WarehouseRobot::<ObjectMalfunctioning>::from(lines(SMALL_SAMPLE));
+        assert_eq!(wide.box_gps_coordinates(), [106, 110, 208, 308, 408, 508]);
     }
 }
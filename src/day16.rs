@@ -23,7 +23,9 @@ pub fn run() {
 }
 
 fn part1(reindeer_maze: ReindeerMaze) -> u32 {
-    reindeer_maze.find_lowest_cost()
+    reindeer_maze
+        .find_lowest_cost()
+        .expect("The end of the maze can't be reached")
 }
 
 fn part2(reindeer_maze: ReindeerMaze) -> u32 {
@@ -35,6 +37,8 @@ struct ReindeerMaze {
     start: Coordinate<isize>,
     end: Coordinate<isize>,
     maze: UnsizedGrid<Objects>,
+    /// Path tiles annotated in the input with `O` or an arrow, in reading order
+    overlay: Vec<Coordinate<isize>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -110,6 +114,16 @@ impl ReindeerMaze {
     const NINETY_DEGREE_TURN_MULTIPLIER: u32 = 1001;
 
     fn count_tiles_in_best_path(&self) -> u32 {
+        let best_path_tiles = self.best_path_tiles();
+        debug_assert!(
+            self.overlay_within(&best_path_tiles),
+            "The annotated path strays off the best paths"
+        );
+        best_path_tiles.len() as u32
+    }
+
+    /// Returns every tile that lies on at least one of the lowest cost paths from start to end
+    fn best_path_tiles(&self) -> HashSet<Coordinate<isize>> {
         let mut cost_at_goal: Option<u32> = None;
         let mut graph = StaticGraph::new();
         let start_ptr = graph.add_node(self.start);
@@ -126,11 +140,11 @@ impl ReindeerMaze {
             .map(|x| Reverse(x)),
         );
 
-        fn count_visited(
+        fn visited_tiles(
             end_ptr: StaticNodePtr,
             graph: &StaticGraph<Coordinate<isize>, ()>,
             unsized_grid: &UnsizedGrid<Objects>,
-        ) -> u32 {
+        ) -> HashSet<Coordinate<isize>> {
             #[cfg(debug_assertions)]
             {
                 // Print the path to a file
@@ -163,14 +177,13 @@ impl ReindeerMaze {
                 .iter()
                 .map(|x| *graph.get(*x).unwrap())
                 .collect::<HashSet<_>>()
-                .len() as u32
         }
 
         let mut cache = HashMap::new();
         while let Some(Reverse(location)) = queue.pop() {
             if let Some(goal_cost) = cost_at_goal {
                 if location.cost > goal_cost {
-                    return count_visited(end_ptr, &graph, &self.maze);
+                    return visited_tiles(end_ptr, &graph, &self.maze);
                 }
             }
 
@@ -193,12 +206,45 @@ impl ReindeerMaze {
             }
         }
 
-        count_visited(end_ptr, &graph, &self.maze)
+        visited_tiles(end_ptr, &graph, &self.maze)
+    }
+
+    /// Checks that every tile annotated in the input's overlay is one of `best_path_tiles`
+    fn overlay_within(&self, best_path_tiles: &HashSet<Coordinate<isize>>) -> bool {
+        self.overlay
+            .iter()
+            .all(|coord| best_path_tiles.contains(coord))
     }
 
-    fn find_lowest_cost(&self) -> u32 {
+    /// Checks if the end can be reached from the start at all, ignoring the cost of turning
+    fn is_solvable(&self) -> bool {
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::from([self.start]);
+        while let Some(curr_coord) = queue.pop_front() {
+            if curr_coord == self.end {
+                return true;
+            }
+            if !visited.insert(curr_coord) {
+                continue;
+            }
+            Direction::direction_list()
+                .map(|dir| curr_coord + dir)
+                .into_iter()
+                .filter(|next| matches!(self.maze.get(next), Some(obj) if *obj != Objects::Wall))
+                .for_each(|next| queue.push_back(next));
+        }
+        false
+    }
+
+    /// Finds the lowest score to get from the start to the end,
+    /// or `None` if the end can't be reached
+    fn find_lowest_cost(&self) -> Option<u32> {
         const INFINITY: u32 = u32::MAX;
 
+        if !self.is_solvable() {
+            return None;
+        }
+
         let mut min_score_grid = UnsizedGrid::transform_from(&self.maze, |_| INFINITY);
 
         let mut queue = VecDeque::with_capacity(self.maze.num_rows() * self.maze.num_cols());
@@ -253,7 +299,7 @@ impl ReindeerMaze {
         }
 
         // Retrieve answer from end coordinate
-        *min_score_grid.get(&self.end).unwrap()
+        Some(*min_score_grid.get(&self.end).unwrap())
     }
 }
 
@@ -281,6 +327,7 @@ impl From<Vec<String>> for ReindeerMaze {
         let mut maze = UnsizedGrid::new(value.len(), value[0].len(), Objects::Path);
         let mut start = None;
         let mut end = None;
+        let mut overlay: Vec<Coordinate<isize>> = vec![];
         for (i, line) in value.iter().enumerate() {
             if line.is_empty() {
                 break;
@@ -288,7 +335,11 @@ impl From<Vec<String>> for ReindeerMaze {
             for (j, e) in line.chars().enumerate() {
                 let obj = match e {
                     '#' => Objects::Wall,
-                    '.' | 'O' | 'v' | '>' | '^' | '<' => Objects::Path,
+                    '.' => Objects::Path,
+                    'O' | 'v' | '>' | '^' | '<' => {
+                        overlay.push(Coordinate::new(i, j).into());
+                        Objects::Path
+                    }
                     'S' => {
                         start = Some(Coordinate::new(i, j));
                         Objects::Start
@@ -306,6 +357,95 @@ impl From<Vec<String>> for ReindeerMaze {
             maze,
             end: end.unwrap().into(),
             start: start.unwrap().into(),
+            overlay,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn maze(rows: &[&str]) -> ReindeerMaze {
+        ReindeerMaze::from(
+            rows.iter()
+                .map(|&row| String::from(row))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    const FIRST_SAMPLE: [&str; 15] = [
+        "###############",
+        "#.......#....E#",
+        "#.#.###.#.###.#",
+        "#.....#.#...#.#",
+        "#.###.#####.#.#",
+        "#.#.#.......#.#",
+        "#.#####.###.#.#",
+        "#...........#.#",
+        "###.#.#####.#.#",
+        "#...#.....#.#.#",
+        "#.#.#.###.#.#.#",
+        "#.....#...#.#.#",
+        "#.###.#.#.#.#.#",
+        "#S..#.....#...#",
+        "###############",
+    ];
+
+    #[test]
+    fn records_overlay_positions() {
+        let annotated = maze(&["#######", "#S>OOE#", "#.###.#", "#.....#", "#######"]);
+        assert_eq!(
+            annotated.overlay,
+            [(1, 2), (1, 3), (1, 4)].map(|(i, j)| Coordinate::new(i, j))
+        );
+        assert!(annotated.overlay_within(&annotated.best_path_tiles()));
+        assert_eq!(annotated.count_tiles_in_best_path(), 5);
+
+        // The overlay goes the long way round
+        let detour = maze(&["#######", "#S...E#", "#v###^#", "#>OOO^#", "#######"]);
+        assert_eq!(detour.overlay.len(), 7);
+        assert!(!detour.overlay_within(&detour.best_path_tiles()));
+    }
+
+    #[test]
+    fn lowest_cost_only_when_solvable() {
+        let sample = maze(&FIRST_SAMPLE);
+        assert!(sample.is_solvable());
+        assert_eq!(sample.find_lowest_cost(), Some(7036));
+
+        let walled_off = maze(&["#######", "#S.#..#", "#..#.E#", "#######"]);
+        assert!(!walled_off.is_solvable());
+        assert_eq!(walled_off.find_lowest_cost(), None);
+    }
+
+    #[test]
+    fn best_path_tiles_on_samples() {
+        let first = maze(&FIRST_SAMPLE);
+        let tiles = first.best_path_tiles();
+        assert_eq!(tiles.len(), 45);
+        assert!(tiles.contains(&first.start) && tiles.contains(&first.end));
+
+        let second = maze(&[
+            "#################",
+            "#...#...#...#..E#",
+            "#.#.#.#.#.#.#.#.#",
+            "#.#.#.#...#...#.#",
+            "#.#.#.#.###.#.#.#",
+            "#...#.#.#.....#.#",
+            "#.#.#.#.#.#####.#",
+            "#.#...#.#.#.....#",
+            "#.#.#####.#.###.#",
+            "#.#.#.......#...#",
+            "#.#.###.#####.###",
+            "#.#.#...#.....#.#",
+            "#.#.#.#####.###.#",
+            "#.#.#.........#.#",
+            "#.#.#.#########.#",
+            "#S#.............#",
+            "#################",
+        ]);
+        assert_eq!(second.best_path_tiles().len(), 64);
+        assert_eq!(second.count_tiles_in_best_path(), 64);
+    }
+}
@@ -1,4 +1,5 @@
 use aoc_utils_rust::day_setup::Utils;
+use std::fmt;
 use std::iter::Sum;
 use std::ops::{Add, Deref};
 use std::slice::Iter;
@@ -64,8 +65,28 @@ impl Computer {
         res
     }
 
+    /// Number of opcodes `run_all` executes before deciding the program never halts
+    const DEFAULT_STEP_LIMIT: u64 = 1_000_000;
+
     fn run_all(&mut self) -> Output {
-        self.flatten().sum::<Output>()
+        self.run_with_limit(Self::DEFAULT_STEP_LIMIT)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Runs the program until it halts, executing at most `max_steps` opcodes so that a program
+    /// whose `jnz` never falls through doesn't loop forever.
+    fn run_with_limit(&mut self, max_steps: u64) -> Result<Output, StepLimitExceeded> {
+        let max_steps_usize = usize::try_from(max_steps).unwrap_or(usize::MAX);
+        let output = self
+            .by_ref()
+            .take(max_steps_usize)
+            .flatten()
+            .sum::<Output>();
+        if self.should_halt() {
+            Ok(output)
+        } else {
+            Err(StepLimitExceeded(max_steps))
+        }
     }
 
     fn reset(&mut self, reg_a: u64) {
@@ -76,17 +97,26 @@ impl Computer {
     }
 
     fn find_starting_a_reg(&mut self) -> u64 {
+        self.all_self_referential_a()
+            .into_iter()
+            .min()
+            .expect("No value of register A outputs the program")
+    }
+
+    /// Finds every value of register A for which the program outputs itself, in ascending order.
+    fn all_self_referential_a(&self) -> Vec<u64> {
+        let mut computer = self.clone();
         let mut valid_values = vec![0];
 
-        for &instr in self.instruction.clone().iter().rev() {
+        for &instr in self.instruction.iter().rev() {
             let mut next_vals = Vec::new();
 
             for a in &valid_values {
                 let shifted_a = a * 8;
 
                 for candidate in shifted_a..shifted_a + 8 {
-                    self.reset(candidate);
-                    let out = self.run_all();
+                    computer.reset(candidate);
+                    let out = computer.run_all();
                     if let Some(&first) = out.first() {
                         if first == instr {
                             next_vals.push(candidate);
@@ -98,7 +128,58 @@ impl Computer {
             valid_values = next_vals
         }
 
-        *valid_values.iter().min().unwrap()
+        // Only keep the values whose whole output matches the program
+        valid_values.retain(|&a| {
+            computer.reset(a);
+            computer.run_all().as_slice() == &*self.instruction
+        });
+        valid_values.sort_unstable();
+        valid_values.dedup();
+        valid_values
+    }
+
+    /// Renders each instruction of the program as a mnemonic followed by its operand,
+    /// e.g. `[0, 1, 5, 4]` => `["adv 1", "out A"]`.
+    /// Combo operands are shown as the register they read from, literal operands as numbers.
+    #[cfg(test)]
+    fn disassemble(&self) -> Vec<String> {
+        fn combo(operand: u8) -> String {
+            match operand {
+                0..=3 => operand.to_string(),
+                4 => "A".into(),
+                5 => "B".into(),
+                6 => "C".into(),
+                _ => unreachable!("Invalid operand: {}", operand),
+            }
+        }
+
+        self.instruction
+            .chunks(2)
+            .map(|chunk| {
+                let (opcode, operand) = (chunk[0], chunk.get(1).copied().unwrap_or(0));
+                match opcode {
+                    0 => format!("adv {}", combo(operand)),
+                    1 => format!("bxl {}", operand),
+                    2 => format!("bst {}", combo(operand)),
+                    3 => format!("jnz {}", operand),
+                    4 => "bxc".into(), // Operand is read but never used
+                    5 => format!("out {}", combo(operand)),
+                    6 => format!("bdv {}", combo(operand)),
+                    7 => format!("cdv {}", combo(operand)),
+                    _ => unreachable!("Invalid instruction: {}", opcode),
+                }
+            })
+            .collect()
+    }
+}
+
+/// The program was still running after executing the given number of opcodes
+#[derive(Debug)]
+struct StepLimitExceeded(u64);
+
+impl fmt::Display for StepLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Program did not halt within {} steps", self.0)
     }
 }
 
@@ -254,3 +335,60 @@ impl From<Vec<String>> for Computer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn computer(reg_a: u64, program: &str) -> Computer {
+        Computer::from(vec![
+            format!("Register A: {}", reg_a),
+            "Register B: 0".to_string(),
+            "Register C: 0".to_string(),
+            String::new(),
+            format!("Program: {}", program),
+        ])
+    }
+
+    #[test]
+    fn disassembles_sample_program() {
+        assert_eq!(
+            computer(729, "0,1,5,4,3,0").disassemble(),
+            ["adv 1", "out A", "jnz 0"]
+        );
+        assert_eq!(
+            computer(0, "2,4,1,3,7,5,4,1,0,3,5,5,3,0").disassemble(),
+            ["bst A", "bxl 3", "cdv B", "bxc", "adv 3", "out B", "jnz 0"]
+        );
+    }
+
+    #[test]
+    fn run_with_limit_stops_looping_program() {
+        // `jnz 0` with a non-zero A register jumps back to itself forever
+        assert!(matches!(
+            computer(1, "3,0").run_with_limit(50),
+            Err(StepLimitExceeded(50))
+        ));
+        assert_eq!(
+            computer(729, "0,1,5,4,3,0")
+                .run_with_limit(1_000)
+                .unwrap()
+                .display(),
+            "4,6,3,5,6,3,5,2,1,0"
+        );
+    }
+
+    #[test]
+    fn finds_sorted_quine_starting_values() {
+        let mut quine = computer(2024, "0,3,5,4,3,0");
+        let values = quine.all_self_referential_a();
+
+        assert_eq!(values.first(), Some(&117440));
+        assert!(values.windows(2).all(|pair| pair[0] < pair[1]));
+        for &a in &values {
+            quine.reset(a);
+            assert_eq!(quine.run_all().as_slice(), &*quine.instruction);
+        }
+        assert_eq!(quine.find_starting_a_reg(), 117440);
+    }
+}
@@ -4,6 +4,7 @@ use aoc_utils_rust::day_setup::Utils;
 use aoc_utils_rust::grid::sized_grid::SizedGrid;
 use aoc_utils_rust::grid::{Grid, GridMut};
 use aoc_utils_rust::miscellaneous::the_visitor::{TheVisitor, Timer};
+use std::cmp::Ordering;
 use std::collections::VecDeque;
 use std::fmt::Debug;
 
@@ -20,8 +21,8 @@ pub fn run() {
     Utils::run_part(part2, 2, 18, Some((22, 20)));
 }
 const GRID_SIZE: usize = 71;
-type TimerMap = SizedGrid<Timer, GRID_SIZE, GRID_SIZE>;
-type Map = SizedGrid<bool, GRID_SIZE, GRID_SIZE>;
+type TimerMap<const N: usize> = SizedGrid<Timer, N, N>;
+type Map<const N: usize> = SizedGrid<bool, N, N>;
 fn part1(corruption_byte_stream: CorruptedByteStream) -> u32 {
     let mut map = SizedGrid::<_, GRID_SIZE, GRID_SIZE>::new(true);
     let mut the_visitor = {
@@ -44,12 +45,15 @@ fn part2(mut corruption_byte_stream: CorruptedByteStream) -> (isize, isize) {
     // Find the max corrupted bytes to escape and transpose the result back (I guess u can avoid transposition if u change the
     // start and coordinates but im too lazy for that
     corruption_byte_stream
-        .find_max_corrupted_bytes_to_escape()
+        .find_first_blocking_byte::<GRID_SIZE>()
         .transpose()
         .into()
 }
 
-fn find_shortest_path(map: &Map, the_visitor: &mut TheVisitor<TimerMap>) -> Option<u32> {
+fn find_shortest_path<const N: usize>(
+    map: &Map<N>,
+    the_visitor: &mut TheVisitor<TimerMap<N>>,
+) -> Option<u32> {
     let end_coord = map.bottom_right_coordinate();
     let mut queue = VecDeque::with_capacity(map.num_cols());
     queue.push_back((Coordinate::<isize>::ORIGIN, 0));
@@ -77,14 +81,23 @@ struct CorruptedByteStream {
     corrupted_stream: Box<[Coordinate<isize>]>,
 }
 impl CorruptedByteStream {
-    fn toggle_corrupted_bytes(map: &mut Map, corrupted_stream: &[Coordinate<isize>]) {
+    fn toggle_corrupted_bytes<const N: usize>(
+        map: &mut Map<N>,
+        corrupted_stream: &[Coordinate<isize>],
+    ) {
         for coord in corrupted_stream.iter() {
             *map.get_mut(coord).unwrap() ^= true;
         }
     }
 
-    fn find_max_corrupted_bytes_to_escape(&self) -> Coordinate<isize> {
-        let mut map = SizedGrid::<bool, GRID_SIZE, GRID_SIZE>::new(true);
+    /// Finds the first byte that cuts the start off from the exit by binary searching over how
+    /// many bytes have fallen, toggling the bytes between successive midpoints.
+    ///
+    /// Superseded by `find_first_blocking_byte`, as toggling goes wrong when a coordinate
+    /// appears more than once in the stream.
+    #[cfg(test)]
+    fn find_max_corrupted_bytes_to_escape<const N: usize>(&self) -> Coordinate<isize> {
+        let mut map = Map::<N>::new(true);
         let mut the_visitor = {
             let backing_grid = SizedGrid::with_size_from(&map, Timer::BLANK);
             TheVisitor::new(backing_grid)
@@ -122,6 +135,111 @@ impl CorruptedByteStream {
 
         result.unwrap().into()
     }
+
+    /// Finds the first byte that cuts the start off from the exit by working backwards:
+    /// starting with every byte corrupted, bytes are cleared in reverse order while a union-find
+    /// tracks which open cells are connected, until the start and exit first join up.
+    ///
+    /// A coordinate that appears more than once in the stream stays corrupted until its first
+    /// occurrence is cleared.
+    fn find_first_blocking_byte<const N: usize>(&self) -> Coordinate<isize> {
+        fn connect_open_neighbours<const N: usize>(
+            map: &Map<N>,
+            union_find: &mut UnionFind,
+            coord: Coordinate<isize>,
+        ) {
+            let cell = union_find.index(&coord);
+            Direction::direction_list()
+                .map(|dir| coord + dir)
+                .iter()
+                .filter(|next| map.get(next).is_some_and(|&is_open| is_open))
+                .for_each(|next| {
+                    let next = union_find.index(next);
+                    union_find.union(cell, next)
+                });
+        }
+
+        let mut map = Map::<N>::new(true);
+        for coord in self.corrupted_stream.iter() {
+            *map.get_mut(coord).unwrap() = false;
+        }
+
+        let mut union_find = UnionFind::new(N, N);
+        for i in 0..N as isize {
+            for j in 0..N as isize {
+                let coord = Coordinate::new(i, j);
+                if *map.get(&coord).unwrap() {
+                    connect_open_neighbours(&map, &mut union_find, coord);
+                }
+            }
+        }
+
+        // Position in the stream where each cell first gets corrupted
+        let mut first_corrupted = vec![usize::MAX; N * N];
+        for (idx, coord) in self.corrupted_stream.iter().enumerate().rev() {
+            first_corrupted[union_find.index(coord)] = idx;
+        }
+
+        let start = union_find.index(&Coordinate::ORIGIN);
+        let end = union_find.index(&map.bottom_right_coordinate().into());
+        for (idx, &coord) in self.corrupted_stream.iter().enumerate().rev() {
+            if first_corrupted[union_find.index(&coord)] != idx {
+                continue; // Still corrupted by an earlier byte
+            }
+            *map.get_mut(&coord).unwrap() = true;
+            connect_open_neighbours(&map, &mut union_find, coord);
+            if union_find.find(start) == union_find.find(end) {
+                return coord;
+            }
+        }
+
+        panic!("The exit can be reached even with every byte corrupted")
+    }
+}
+
+/// Disjoint sets over the cells of the map, indexed in row-major order
+struct UnionFind {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    num_cols: usize,
+}
+
+impl UnionFind {
+    fn new(num_rows: usize, num_cols: usize) -> Self {
+        Self {
+            parent: (0..num_rows * num_cols).collect(),
+            rank: vec![0; num_rows * num_cols],
+            num_cols,
+        }
+    }
+
+    fn index(&self, coord: &Coordinate<isize>) -> usize {
+        coord.i as usize * self.num_cols + coord.j as usize
+    }
+
+    fn find(&mut self, mut node: usize) -> usize {
+        while self.parent[node] != node {
+            // Path halving
+            self.parent[node] = self.parent[self.parent[node]];
+            node = self.parent[node];
+        }
+        node
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        match self.rank[a].cmp(&self.rank[b]) {
+            Ordering::Less => self.parent[a] = b,
+            Ordering::Greater => self.parent[b] = a,
+            Ordering::Equal => {
+                self.parent[b] = a;
+                self.rank[a] += 1;
+            }
+        }
+    }
 }
 
 impl From<Vec<String>> for CorruptedByteStream {
@@ -136,3 +254,101 @@ impl From<Vec<String>> for CorruptedByteStream {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_SIZE: usize = 7;
+    const SAMPLE: [&str; 25] = [
+        "5,4", "4,2", "4,5", "3,0", "2,1", "6,3", "2,4", "1,5", "0,6", "3,3", "2,6", "5,1", "1,2",
+        "5,5", "2,5", "6,5", "1,4", "0,4", "6,4", "1,1", "6,1", "1,0", "0,5", "1,6", "2,0",
+    ];
+
+    fn sample_stream() -> CorruptedByteStream {
+        CorruptedByteStream::from(SAMPLE.map(String::from).to_vec())
+    }
+
+    /// A wall down column 35 that's only complete once its bottom cell falls, mixed in with
+    /// bytes down column 10 that always leave its bottom cell open
+    fn wall_stream() -> Vec<Coordinate<isize>> {
+        (0..GRID_SIZE as isize - 1)
+            .flat_map(|i| [Coordinate::new(i, 35), Coordinate::new(i, 10)])
+            .chain([Coordinate::new(70, 35), Coordinate::new(3, 50)])
+            .collect()
+    }
+
+    /// Reference answer: the byte after the longest prefix of the stream that can still be escaped
+    fn first_blocking_by_bfs(stream: &[Coordinate<isize>]) -> Coordinate<isize> {
+        let mut the_visitor = TheVisitor::new(TimerMap::<GRID_SIZE>::new(Timer::BLANK));
+        let blocked_after = (1..=stream.len())
+            .find(|&n| {
+                let mut map = Map::<GRID_SIZE>::new(true);
+                for coord in &stream[..n] {
+                    *map.get_mut(coord).unwrap() = false;
+                }
+                the_visitor.clear();
+                find_shortest_path(&map, &mut the_visitor).is_none()
+            })
+            .unwrap();
+        stream[blocked_after - 1]
+    }
+
+    #[test]
+    fn first_blocking_byte_matches_bfs() {
+        let stream = wall_stream();
+        let byte_stream = CorruptedByteStream {
+            corrupted_stream: stream.clone().into(),
+        };
+        assert_eq!(
+            byte_stream.find_first_blocking_byte::<GRID_SIZE>(),
+            Coordinate::new(70, 35)
+        );
+        assert_eq!(
+            byte_stream.find_first_blocking_byte::<GRID_SIZE>(),
+            first_blocking_by_bfs(&stream)
+        );
+    }
+
+    #[test]
+    fn first_blocking_byte_ignores_repeated_coordinates() {
+        // Corrupting the top of the wall again must not reopen it when the repeat is cleared
+        let mut stream = wall_stream();
+        stream.push(Coordinate::new(0, 35));
+        let byte_stream = CorruptedByteStream {
+            corrupted_stream: stream.clone().into(),
+        };
+        assert_eq!(
+            byte_stream.find_first_blocking_byte::<GRID_SIZE>(),
+            Coordinate::new(70, 35)
+        );
+        assert_eq!(
+            byte_stream.find_first_blocking_byte::<GRID_SIZE>(),
+            first_blocking_by_bfs(&stream)
+        );
+    }
+
+    #[test]
+    fn sample_shortest_path() {
+        let mut map = Map::<SAMPLE_SIZE>::new(true);
+        let mut the_visitor = TheVisitor::new(TimerMap::<SAMPLE_SIZE>::new(Timer::BLANK));
+        CorruptedByteStream::toggle_corrupted_bytes(
+            &mut map,
+            &sample_stream().corrupted_stream[..12],
+        );
+        assert_eq!(find_shortest_path(&map, &mut the_visitor), Some(22));
+    }
+
+    #[test]
+    fn sample_first_blocking_byte() {
+        let stream = sample_stream();
+        let expected = stream.corrupted_stream[20];
+        assert_eq!(SAMPLE[20], "6,1");
+
+        assert_eq!(stream.find_first_blocking_byte::<SAMPLE_SIZE>(), expected);
+        assert_eq!(
+            stream.find_max_corrupted_bytes_to_escape::<SAMPLE_SIZE>(),
+            expected
+        );
+    }
+}
@@ -85,3 +85,61 @@ fn is_valid_design<'a>(
     cache.insert(towel, false);
     false
 }
+
+/// Finds one sequence of patterns which concatenate into the design, or `None` if the design
+/// can't be made from the given patterns.
+#[cfg(test)]
+fn one_decomposition<'a>(design: &str, patterns: &[&'a str]) -> Option<Vec<&'a str>> {
+    fn search<'a, 'd>(
+        design: &'d str,
+        patterns: &[&'a str],
+        used: &mut Vec<&'a str>,
+        dead_ends: &mut std::collections::HashSet<&'d str>,
+    ) -> bool {
+        if design.is_empty() {
+            return true;
+        } else if dead_ends.contains(design) {
+            return false;
+        }
+
+        for &pattern in patterns {
+            if let Some(rest) = design.strip_prefix(pattern) {
+                used.push(pattern);
+                if search(rest, patterns, used, dead_ends) {
+                    return true;
+                }
+                used.pop();
+            }
+        }
+
+        dead_ends.insert(design);
+        false
+    }
+
+    let mut used = vec![];
+    search(
+        design,
+        patterns,
+        &mut used,
+        &mut std::collections::HashSet::new(),
+    )
+    .then_some(used)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATTERNS: [&str; 8] = ["r", "wr", "b", "g", "bwu", "rb", "gb", "br"];
+
+    #[test]
+    fn decomposition_concatenates_to_design() {
+        for design in ["brwrr", "bggr", "gbbr", "rrbgbr", "bwurrg", "brgr"] {
+            let used = one_decomposition(design, &PATTERNS).unwrap();
+            assert!(used.iter().all(|pattern| PATTERNS.contains(pattern)));
+            assert_eq!(used.concat(), design);
+        }
+        assert_eq!(one_decomposition("ubwu", &PATTERNS), None);
+        assert_eq!(one_decomposition("bbrgwb", &PATTERNS), None);
+    }
+}
@@ -26,18 +26,7 @@ fn part2(input: Report) -> usize {
     input
         .levels
         .into_iter()
-        .filter(|nums| {
-            // Check if the report is valid as-is
-            if Report::is_level_ok(nums) {
-                return true;
-            }
-            // Otherwise, check by removing one level at a time
-            nums.iter().enumerate().any(|(i, _)| {
-                let mut temp = nums.clone();
-                temp.remove(i);
-                Report::is_level_ok(&temp)
-            })
-        })
+        .filter(|nums| Report::is_safe_removing_up_to(nums, 1))
         .count()
 }
 
@@ -47,19 +36,84 @@ struct Report {
 
 impl Report {
     fn is_level_ok(level: &[u8]) -> bool {
-        let mut ordering = level[1].cmp(&level[0]);
-        if !(1..=3).contains(&level[1].abs_diff(level[0])) {
+        Self::is_monotonic_within(level, 1, 3)
+    }
+
+    /// Counts the safe reports for both parts in one pass over `reader`, parsing a single line at
+    /// a time into a reused buffer instead of collecting every level up front.
+    ///
+    /// # Returns
+    ///
+    /// The number of reports that are safe as-is and the number that are safe after removing at
+    /// most one level.
+    #[cfg(test)]
+    fn count_safe_streaming<R: std::io::BufRead>(reader: R) -> (usize, usize) {
+        let mut levels = Vec::new();
+        let mut counts = (0, 0);
+
+        for line in reader.lines() {
+            let line = line.expect("Failed to read report");
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            levels.clear();
+            levels.extend(line.split_whitespace().map(|e| e.parse::<u8>().unwrap()));
+
+            if Self::is_level_ok(&levels) {
+                // Safe as-is also means safe with a removal
+                counts.0 += 1;
+                counts.1 += 1;
+            } else if Self::is_safe_removing_up_to(&levels, 1) {
+                counts.1 += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// Checks if the report is valid as-is or can be made valid by removing at most `k` levels.
+    /// Brute forces every combination of removals, so `k` should stay small.
+    fn is_safe_removing_up_to(levels: &[u8], k: usize) -> bool {
+        // Check if the report is valid as-is
+        if Self::is_level_ok(levels) {
+            return true;
+        }
+        if k == 0 {
+            return false;
+        }
+        // Otherwise, check by removing one level at a time
+        (0..levels.len()).any(|i| {
+            let mut temp = levels.to_vec();
+            temp.remove(i);
+            Self::is_safe_removing_up_to(&temp, k - 1)
+        })
+    }
+
+    /// Checks that `levels` is strictly increasing or strictly decreasing, with every
+    /// adjacent pair differing by at least `min_step` and at most `max_step`.
+    fn is_monotonic_within(levels: &[u8], min_step: u8, max_step: u8) -> bool {
+        match levels.len() {
+            // An empty report has no levels to be safe
+            0 => return false,
+            // A single level can't break the rules
+            1 => return true,
+            _ => {}
+        }
+        let step_range = min_step..=max_step;
+        let mut ordering = levels[1].cmp(&levels[0]);
+        if !step_range.contains(&levels[1].abs_diff(levels[0])) {
             return false;
         }
-        level[1..].windows(2).all(|window| match window {
+        levels[1..].windows(2).all(|window| match window {
             [a, b] => match (ordering, b.cmp(a)) {
                 (Ordering::Greater, Ordering::Greater) => {
                     ordering = Ordering::Greater;
-                    (1..=3).contains(&(b - a))
+                    step_range.contains(&(b - a))
                 }
                 (Ordering::Less, Ordering::Less) => {
                     ordering = Ordering::Less;
-                    (1..=3).contains(&(a - b))
+                    step_range.contains(&(a - b))
                 }
                 _ => false,
             },
@@ -73,6 +127,7 @@ impl From<Vec<String>> for Report {
         Self {
             levels: value
                 .iter()
+                .filter(|line| !line.trim().is_empty())
                 .map(|line| {
                     line.split_whitespace()
                         .map(|e| e.parse::<u8>().unwrap())
@@ -82,3 +137,58 @@ impl From<Vec<String>> for Report {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9
+
+5
+";
+
+    fn sample() -> Report {
+        Report::from(SAMPLE.lines().map(String::from).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn streaming_matches_collected_report() {
+        let streamed = Report::count_safe_streaming(SAMPLE.as_bytes());
+
+        assert_eq!(streamed, (part1(sample()), part2(sample())));
+        // The published six reports plus the single-level one, which is trivially safe,
+        // while the blank line isn't a report at all
+        assert_eq!(streamed, (3, 5));
+        assert_eq!(sample().levels.len(), 7);
+    }
+
+    #[test]
+    fn monotonic_within_custom_step_range() {
+        // Steps of 4 and 5 are only allowed by the wider range
+        assert!(Report::is_monotonic_within(&[1, 5, 10, 12], 1, 5));
+        assert!(!Report::is_monotonic_within(&[1, 5, 10, 12], 1, 3));
+        assert!(Report::is_monotonic_within(&[12, 10, 5, 1], 1, 5));
+        assert!(!Report::is_monotonic_within(&[12, 10, 5, 1], 1, 3));
+        // A lone level can't break either range, but an empty report isn't safe
+        assert!(Report::is_monotonic_within(&[7], 1, 3));
+        assert!(!Report::is_monotonic_within(&[], 1, 3));
+    }
+
+    #[test]
+    fn removals_up_to_k() {
+        // Two bad levels (7 and 8) in an otherwise safe increasing report
+        let levels = [1, 2, 7, 8, 3, 4];
+
+        assert!(!Report::is_safe_removing_up_to(&levels, 0));
+        assert!(!Report::is_safe_removing_up_to(&levels, 1));
+        assert!(Report::is_safe_removing_up_to(&levels, 2));
+        // Safe reports need no removals at all
+        assert!(Report::is_safe_removing_up_to(&[7, 6, 4, 2, 1], 0));
+    }
+}
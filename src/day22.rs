@@ -1,5 +1,5 @@
 use aoc_utils_rust::day_setup::Utils;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ops::{Deref, DerefMut};
 
 /// Runs the Advent of Code puzzles for [Current Day](https://adventofcode.com/2024/day/22).
@@ -15,55 +15,85 @@ pub fn run() {
     Utils::run_part(part2, 2, 22, Some(1582));
 }
 
+/// Number of secret numbers each buyer generates in a day
+const SEQUENCE_LEN: usize = 2000;
+
 fn part1(mut buyers: Buyers) -> u64 {
-    buyers.iter_mut().map(SecretNumber::next_2000).sum()
+    buyers
+        .iter_mut()
+        .map(|secret_number| secret_number.nth_secret(SEQUENCE_LEN))
+        .sum()
 }
 
+#[cfg(not(feature = "rayon"))]
 fn part2(mut buyers: Buyers) -> u64 {
     let mut dict = HashMap::new();
     buyers.iter_mut().for_each(|secret_number| {
-        add_to_diff_dict(secret_number, &mut dict);
+        add_to_diff_dict(secret_number, &mut dict, SEQUENCE_LEN);
     });
     *dict.values().max().unwrap() as u64
 }
 
-fn add_to_diff_dict(secret_number: &mut SecretNumber, diff_dict: &mut HashMap<[i8; 4], i32>) {
-    const NUM_OF_BUYERS: usize = 2000;
+#[cfg(feature = "rayon")]
+fn part2(mut buyers: Buyers) -> u64 {
+    buyers.best_total_parallel()
+}
+
+/// Adds the price at the first occurrence of every window of price changes
+/// within the buyer's next `sequence_len` secret numbers to `diff_dict`
+fn add_to_diff_dict(
+    secret_number: &mut SecretNumber,
+    diff_dict: &mut HashMap<[i8; 4], i32>,
+    sequence_len: usize,
+) {
     const WINDOW_SIZE: usize = 4;
 
-    let mut diffs = [0i8; NUM_OF_BUYERS];
-    let mut nums = [0i8; NUM_OF_BUYERS - WINDOW_SIZE + 1];
+    // Only the first occurrence of a window counts as that's when the monkey sells
+    let mut seen = HashSet::with_capacity(sequence_len.saturating_sub(WINDOW_SIZE - 1));
+    // Rolling window over the last `WINDOW_SIZE` price changes
+    let mut window = [0i8; WINDOW_SIZE];
 
-    let start = (secret_number.0 % 10) as i8;
-    secret_number
-        .take(NUM_OF_BUYERS)
+    let mut prev = (secret_number.0 % 10) as i8;
+    for (i, price) in secret_number
+        .take(sequence_len)
         .map(|e| (e % 10) as i8)
         .enumerate()
-        .fold(start, |acc, (i, e)| {
-            diffs[i] = e - acc;
-            if i as isize - (WINDOW_SIZE as isize - 1) >= 0 {
-                nums[i - (WINDOW_SIZE - 1)] = e
-            }
-            e
-        });
+    {
+        window.rotate_left(1);
+        window[WINDOW_SIZE - 1] = price - prev;
+        prev = price;
 
-    let mut acc = HashMap::with_capacity(NUM_OF_BUYERS - WINDOW_SIZE + 1);
+        if i >= WINDOW_SIZE - 1 && seen.insert(window) {
+            *diff_dict.entry(window).or_insert(0) += price as i32;
+        }
+    }
+}
 
-    diffs
-        .windows(WINDOW_SIZE)
-        .zip(nums)
-        .for_each(|(window, e)| {
-            if let &[a, b, c, d] = window {
-                acc.entry([a, b, c, d]).or_insert(e);
-            }
-        });
+struct Buyers(Box<[SecretNumber]>);
 
-    acc.into_iter().for_each(|(k, v)| {
-        *diff_dict.entry(k).or_insert(0) += v as i32;
-    });
+#[cfg(feature = "rayon")]
+impl Buyers {
+    /// Parallel version of part 2 which builds a diff dictionary per thread
+    /// before merging them into one to find the best total.
+    fn best_total_parallel(&mut self) -> u64 {
+        use rayon::prelude::*;
+
+        let dict = self
+            .par_iter_mut()
+            .fold(HashMap::new, |mut dict, secret_number| {
+                add_to_diff_dict(secret_number, &mut dict, SEQUENCE_LEN);
+                dict
+            })
+            .reduce(HashMap::new, |mut acc, dict| {
+                dict.into_iter().for_each(|(k, v)| {
+                    *acc.entry(k).or_insert(0) += v;
+                });
+                acc
+            });
+        *dict.values().max().unwrap() as u64
+    }
 }
 
-struct Buyers(Box<[SecretNumber]>);
 impl Deref for Buyers {
     type Target = [SecretNumber];
 
@@ -90,8 +120,9 @@ impl Deref for SecretNumber {
 }
 
 impl SecretNumber {
-    fn next_2000(&mut self) -> u64 {
-        self.take(2000).last().expect("No numbers generated")
+    /// Advances the secret number `n` times, returning the `n`th new secret number
+    fn nth_secret(&mut self, n: usize) -> u64 {
+        self.take(n).last().expect("No numbers generated")
     }
 }
 
@@ -132,3 +163,63 @@ impl From<Vec<String>> for Buyers {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_buyers() -> Buyers {
+        Buyers::from(vec!["1".into(), "2".into(), "3".into(), "2024".into()])
+    }
+
+    /// Builds the diff dictionary the long way, from every price of every buyer up front
+    fn reference_diff_dict(seeds: &[u64], sequence_len: usize) -> HashMap<[i8; 4], i32> {
+        let mut dict = HashMap::new();
+        for &seed in seeds {
+            let prices = std::iter::once(seed)
+                .chain(SecretNumber(seed).take(sequence_len))
+                .map(|e| (e % 10) as i8)
+                .collect::<Vec<_>>();
+            let diffs = prices.windows(2).map(|w| w[1] - w[0]).collect::<Vec<_>>();
+
+            let mut seen = HashSet::new();
+            for (i, window) in diffs.windows(4).enumerate() {
+                let window: [i8; 4] = window.try_into().unwrap();
+                if seen.insert(window) {
+                    *dict.entry(window).or_insert(0) += prices[i + 4] as i32;
+                }
+            }
+        }
+        dict
+    }
+
+    #[test]
+    fn diff_dict_matches_reference() {
+        let mut dict = HashMap::new();
+        sample_buyers().iter_mut().for_each(|secret_number| {
+            add_to_diff_dict(secret_number, &mut dict, SEQUENCE_LEN);
+        });
+
+        assert_eq!(dict, reference_diff_dict(&[1, 2, 3, 2024], SEQUENCE_LEN));
+        assert_eq!(dict[&[-2, 1, -1, 3]], 23);
+        assert_eq!(part2(sample_buyers()), 23);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_best_total_matches_sequential() {
+        let reference = reference_diff_dict(&[1, 2, 3, 2024], SEQUENCE_LEN);
+        assert_eq!(
+            sample_buyers().best_total_parallel(),
+            *reference.values().max().unwrap() as u64
+        );
+    }
+
+    #[test]
+    fn nth_secret_matches_published_sequence() {
+        assert_eq!(SecretNumber(123).nth_secret(1), 15887950);
+        assert_eq!(SecretNumber(123).nth_secret(10), 5908254);
+        assert_eq!(SecretNumber(1).nth_secret(SEQUENCE_LEN), 8685429);
+        assert_eq!(SecretNumber(2024).nth_secret(SEQUENCE_LEN), 8667524);
+    }
+}
@@ -1,4 +1,5 @@
 use aoc_utils_rust::day_setup::Utils;
+use std::fmt;
 
 /// Runs the Advent of Code puzzles for [Current Day](https://adventofcode.com/2024/day/25).
 ///
@@ -36,41 +37,167 @@ impl Lock {
 #[derive(Debug, Eq, PartialEq, Hash)]
 struct Key([u8; 5]);
 
+/// Why a schematic in the input couldn't be read, with schematics and rows numbered from 0
+#[derive(Debug)]
+enum SchematicError {
+    /// (schematic, number of rows it has)
+    WrongHeight(usize, usize),
+    /// (schematic, row, number of characters in the row)
+    WrongWidth(usize, usize, usize),
+    /// The offending character and its `(schematic, row, col)` position
+    InvalidGlyph(char, (usize, usize, usize)),
+    /// The top and bottom rows aren't a full row of `#` paired with a full row of `.`
+    MixedMarker(usize),
+    /// (schematic, column) whose `#`s don't form one unbroken pin from the marker row
+    BrokenColumn(usize, usize),
+}
+
+impl fmt::Display for SchematicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchematicError::WrongHeight(schematic, rows) => write!(
+                f,
+                "Schematic {} has {} rows instead of {}",
+                schematic, rows, SCHEMATIC_HEIGHT
+            ),
+            SchematicError::WrongWidth(schematic, row, width) => write!(
+                f,
+                "Row {} of schematic {} has {} columns instead of {}",
+                row, schematic, width, SCHEMATIC_WIDTH
+            ),
+            SchematicError::InvalidGlyph(c, pos) => {
+                write!(
+                    f,
+                    "Invalid character `{}` in the schematics at {:?}",
+                    c, pos
+                )
+            }
+            SchematicError::MixedMarker(schematic) => write!(
+                f,
+                "Schematic {} is neither a lock nor a key as its top and bottom rows are mixed",
+                schematic
+            ),
+            SchematicError::BrokenColumn(schematic, column) => write!(
+                f,
+                "Column {} of schematic {} has a gap in its pin",
+                column, schematic
+            ),
+        }
+    }
+}
+
+const SCHEMATIC_HEIGHT: usize = 7;
+const SCHEMATIC_WIDTH: usize = 5;
+
+/// Panicking version of `try_parse_input` for trusted inputs
 fn parse_input(input: Vec<String>) -> (Vec<Lock>, Vec<Key>) {
-    let mut input = input.into_iter();
+    try_parse_input(&input).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Parses the blank line separated schematics into locks and keys, checking that each one is
+/// `SCHEMATIC_HEIGHT` rows of `SCHEMATIC_WIDTH` columns with a solid marker row on one end,
+/// an empty row on the other, and pins that grow unbroken from the marker row.
+fn try_parse_input(input: &[String]) -> Result<(Vec<Lock>, Vec<Key>), SchematicError> {
     let mut locks = vec![];
     let mut keys = vec![];
-    loop {
-        let first = input.next().unwrap();
-
-        let mut structure = [0; 5];
-
-        for line in [
-            input.next().unwrap(),
-            input.next().unwrap(),
-            input.next().unwrap(),
-            input.next().unwrap(),
-            input.next().unwrap(),
-        ] {
-            for (i, c) in line.chars().enumerate() {
-                if c == '#' {
-                    structure[i] += 1;
-                }
+
+    let schematics = input
+        .split(|line| line.is_empty())
+        .filter(|rows| !rows.is_empty());
+
+    for (schematic, rows) in schematics.enumerate() {
+        if rows.len() != SCHEMATIC_HEIGHT {
+            return Err(SchematicError::WrongHeight(schematic, rows.len()));
+        }
+
+        for (row, line) in rows.iter().enumerate() {
+            if line.chars().count() != SCHEMATIC_WIDTH {
+                return Err(SchematicError::WrongWidth(
+                    schematic,
+                    row,
+                    line.chars().count(),
+                ));
+            }
+            if let Some((col, c)) = line
+                .chars()
+                .enumerate()
+                .find(|&(_, c)| c != '#' && c != '.')
+            {
+                return Err(SchematicError::InvalidGlyph(c, (schematic, row, col)));
             }
         }
 
-        if first.chars().nth(0).unwrap() == '.' {
-            keys.push(Key(structure));
+        let is_filled = |line: &String, glyph: char| line.chars().all(|c| c == glyph);
+        let (top, bottom) = (&rows[0], &rows[SCHEMATIC_HEIGHT - 1]);
+        let is_lock = if is_filled(top, '#') && is_filled(bottom, '.') {
+            true
+        } else if is_filled(top, '.') && is_filled(bottom, '#') {
+            false
         } else {
+            return Err(SchematicError::MixedMarker(schematic));
+        };
+
+        let mut structure = [0; SCHEMATIC_WIDTH];
+        for (col, height) in structure.iter_mut().enumerate() {
+            // Walk each column away from the marker row, where a `#` after a `.` is a gap
+            let mut column = rows
+                .iter()
+                .map(|line| line.as_bytes()[col])
+                .collect::<Vec<_>>();
+            if !is_lock {
+                column.reverse();
+            }
+            if column.windows(2).any(|pair| pair == b".#") {
+                return Err(SchematicError::BrokenColumn(schematic, col));
+            }
+            // The marker row isn't part of the pin
+            *height = column.iter().filter(|&&c| c == b'#').count() as u8 - 1;
+        }
+
+        if is_lock {
             locks.push(Lock(structure));
+        } else {
+            keys.push(Key(structure));
         }
+    }
+
+    Ok((locks, keys))
+}
 
-        let _ = input.next().unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        if input.next().is_none() {
-            break;
-        }
+    const LOCK: [&str; 7] = [
+        "#####", ".####", ".####", ".####", ".#.#.", ".#...", ".....",
+    ];
+    const KEY: [&str; 7] = [
+        ".....", "#....", "#....", "#...#", "#.#.#", "#.###", "#####",
+    ];
+
+    fn schematics(schematics: &[&[&str]]) -> Vec<String> {
+        schematics
+            .iter()
+            .map(|rows| rows.join("\n"))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn parses_valid_schematics() {
+        let (locks, keys) = try_parse_input(&schematics(&[&LOCK, &KEY])).unwrap();
+
+        assert_eq!(locks, [Lock([0, 5, 3, 4, 3])]);
+        assert_eq!(keys, [Key([5, 0, 2, 1, 3])]);
     }
 
-    (locks, keys)
+    #[test]
+    fn rejects_schematic_missing_a_row() {
+        let result = try_parse_input(&schematics(&[&LOCK, &KEY[1..]]));
+
+        assert!(matches!(result, Err(SchematicError::WrongHeight(1, 6))));
+    }
 }
@@ -133,10 +133,20 @@ impl Memory {
         }
     }
 
+    /// `mul` operands are at most this many digits long
+    const MAX_DIGITS: u32 = 3;
+
+    /// Parses a number of at most `MAX_DIGITS` digits.
+    /// Returns `None` if there's no number or it's too long, invalidating the instruction.
     fn parse_number(line_iter: &mut std::iter::Peekable<std::str::Chars>) -> Option<u64> {
         let mut number = None;
+        let mut digits = 0;
         while let Some(c) = line_iter.peek() {
             if c.is_digit(10) {
+                digits += 1;
+                if digits > Self::MAX_DIGITS {
+                    return None;
+                }
                 if number.is_none() {
                     number = Some(0);
                 }
@@ -155,3 +165,38 @@ impl From<Vec<String>> for Memory {
         Self { memory: value }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(line: &str) -> Memory {
+        Memory::from(vec![line.to_string()])
+    }
+
+    #[test]
+    fn operands_limited_to_max_digits() {
+        assert_eq!(part1(memory("mul(123,4)")), 492);
+        assert_eq!(part1(memory("mul(999,999)")), 998001);
+        assert_eq!(part1(memory("mul(1234,5)")), 0);
+        assert_eq!(part1(memory("mul(5,1234)")), 0);
+        // A rejected instruction doesn't swallow the one after it
+        assert_eq!(part1(memory("mul(1234,5)mul(2,3)")), 6);
+    }
+
+    #[test]
+    fn sums_sample_instructions() {
+        assert_eq!(
+            part1(memory(
+                "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))"
+            )),
+            161
+        );
+        assert_eq!(
+            part2(memory(
+                "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"
+            )),
+            48
+        );
+    }
+}
@@ -268,3 +268,81 @@ impl From<Vec<String>> for WordSearch {
         Self::new(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_utils_rust::coordinate_system::direction::FullDirection;
+    use aoc_utils_rust::coordinate_system::Coordinate;
+
+    impl WordSearch {
+        /// Counts the XMAS occurrences reading in a single direction from their `X`.
+        /// Summing over all eight directions gives the same result as `find_all_xmas_instances`.
+        fn count_xmas_in_direction(&self, dir: FullDirection) -> u16 {
+            let letter_at = |coord: Coordinate<isize>| -> Option<u8> {
+                let row = self.words.get(usize::try_from(coord.i).ok()?)?;
+                row.as_bytes().get(usize::try_from(coord.j).ok()?).copied()
+            };
+
+            let mut counter = 0;
+            for (i, word) in self.words.iter().enumerate() {
+                for j in 0..word.len() {
+                    let mut curr = Coordinate::new(i as isize, j as isize);
+                    let mut spells_xmas = true;
+                    for letter in Self::XMAS.bytes() {
+                        if letter_at(curr) != Some(letter) {
+                            spells_xmas = false;
+                            break;
+                        }
+                        curr = curr + dir;
+                    }
+
+                    if spells_xmas {
+                        counter += 1;
+                    }
+                }
+            }
+
+            counter
+        }
+    }
+
+    fn word_search(rows: &[&str]) -> WordSearch {
+        WordSearch::from(rows.iter().map(|row| row.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn counts_single_horizontal_xmas() {
+        let search = word_search(&["..XMAS", "......", "......", "......", "......", "......"]);
+
+        for dir in FullDirection::direction_list() {
+            let expected = u16::from(matches!(dir, FullDirection::East));
+            assert_eq!(search.count_xmas_in_direction(dir), expected);
+        }
+        assert_eq!(search.count_xmas_in_direction(FullDirection::Current), 0);
+        assert_eq!(search.find_all_xmas_instances(), 1);
+    }
+
+    #[test]
+    fn directions_sum_to_total() {
+        let search = word_search(&[
+            "MMMSXXMASM",
+            "MSAMXMSMSA",
+            "AMXSXMAAMM",
+            "MSAMASMSMX",
+            "XMASAMXAMM",
+            "XXAMMXXAMA",
+            "SMSMSASXSS",
+            "SAXAMASAAA",
+            "MAMMMXMMMM",
+            "MXMXAXMASX",
+        ]);
+
+        let by_direction = FullDirection::direction_list()
+            .into_iter()
+            .map(|dir| search.count_xmas_in_direction(dir))
+            .sum::<u16>();
+        assert_eq!(by_direction, 18);
+        assert_eq!(search.find_all_xmas_instances(), by_direction);
+    }
+}
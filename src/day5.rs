@@ -1,6 +1,7 @@
 use aoc_utils_rust::day_setup::Utils;
 use aoc_utils_rust::graph::static_graph::StaticGraph;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 
 /// Runs the Advent of Code puzzles for [Current Day](https://adventofcode.com/2024/day/5).
 ///
@@ -20,7 +21,11 @@ fn part1(book: Books) -> u32 {
 }
 
 fn part2(book: Books) -> u32 {
-    book.partition().1.fix_unordered_books().sum_middle_pages()
+    book.partition()
+        .1
+        .fix_unordered_books()
+        .unwrap_or_else(|err| panic!("{}", err))
+        .sum_middle_pages()
 }
 
 struct UnorderedBooks {
@@ -28,8 +33,27 @@ struct UnorderedBooks {
     unordered_books: Vec<Vec<u8>>,
 }
 
+/// The pages of a book whose rules contradict each other, in the order they form a cycle,
+/// with the first page repeated at the end
+#[derive(Debug)]
+struct RuleCycle(Vec<u8>);
+
+impl fmt::Display for RuleCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Cycle detected in rules between pages {}",
+            self.0
+                .iter()
+                .map(u8::to_string)
+                .collect::<Vec<_>>()
+                .join(" -> ")
+        )
+    }
+}
+
 impl UnorderedBooks {
-    fn fix_unordered_books(self) -> OrderedBooks {
+    fn fix_unordered_books(self) -> Result<OrderedBooks, RuleCycle> {
         let mut ordered_pages = Vec::with_capacity(self.unordered_books.len());
         let mut page_set = HashSet::with_capacity(self.unordered_books.len());
         for book in self.unordered_books {
@@ -37,8 +61,8 @@ impl UnorderedBooks {
             page_set.extend(&book);
 
             let adjacency_list = book
-                .into_iter()
-                .map(|page| {
+                .iter()
+                .map(|&page| {
                     (
                         page,
                         self.rules
@@ -53,16 +77,63 @@ impl UnorderedBooks {
 
             let graph = StaticGraph::<_, ()>::from(adjacency_list);
 
+            // Only search for the offending cycle once the sort has shown there is one
+            let Some(sorted) = graph.topological_sort() else {
+                return Err(RuleCycle(
+                    Self::find_rule_cycle(&self.rules, &book)
+                        .expect("Topological sort only fails on cyclic rules"),
+                ));
+            };
+
             ordered_pages.push(
-                graph
-                    .topological_sort()
-                    .expect("Cycle detected in graph")
+                sorted
                     .into_iter()
                     .map(|ptr| *graph.get(ptr).unwrap())
                     .collect(),
             );
         }
-        OrderedBooks { ordered_pages }
+        Ok(OrderedBooks { ordered_pages })
+    }
+
+    /// Searches the rules restricted to the pages of `book` for a cycle,
+    /// returning the pages forming it if one exists.
+    fn find_rule_cycle(rules: &HashMap<u8, HashSet<u8>>, book: &[u8]) -> Option<Vec<u8>> {
+        fn visit(
+            page: u8,
+            rules: &HashMap<u8, HashSet<u8>>,
+            pages: &HashSet<u8>,
+            path: &mut Vec<u8>,
+            done: &mut HashSet<u8>,
+        ) -> Option<Vec<u8>> {
+            // Revisiting a page on the current path closes a cycle
+            if let Some(start) = path.iter().position(|&p| p == page) {
+                let mut cycle = path[start..].to_vec();
+                cycle.push(page);
+                return Some(cycle);
+            }
+            if done.contains(&page) {
+                return None;
+            }
+
+            path.push(page);
+            for &next in rules.get(&page).into_iter().flatten() {
+                if !pages.contains(&next) {
+                    continue;
+                }
+                if let Some(cycle) = visit(next, rules, pages, path, done) {
+                    return Some(cycle);
+                }
+            }
+            path.pop();
+            done.insert(page);
+
+            None
+        }
+
+        let pages = book.iter().copied().collect::<HashSet<_>>();
+        let mut done = HashSet::with_capacity(book.len());
+        book.iter()
+            .find_map(|&page| visit(page, rules, &pages, &mut Vec::new(), &mut done))
     }
 }
 
@@ -86,6 +157,29 @@ struct Books {
 }
 
 impl Books {
+    /// Checks that no two rules contradict each other, i.e. `a|b` and `b|a`,
+    /// returning the first contradictory pair found in ascending page order.
+    #[cfg(test)]
+    fn validate_rules(&self) -> Result<(), (u8, u8)> {
+        let mut before_pages = self.rules.keys().copied().collect::<Vec<_>>();
+        before_pages.sort_unstable();
+
+        for before in before_pages {
+            let mut after_pages = self.rules[&before].iter().copied().collect::<Vec<_>>();
+            after_pages.sort_unstable();
+
+            if let Some(&after) = after_pages.iter().find(|after| {
+                self.rules
+                    .get(after)
+                    .is_some_and(|pages| pages.contains(&before))
+            }) {
+                return Err((before, after));
+            }
+        }
+
+        Ok(())
+    }
+
     fn partition(self) -> (OrderedBooks, UnorderedBooks) {
         let mut ordered_pages = Vec::with_capacity(self.books.len());
         let mut unordered_pages = Vec::with_capacity(self.books.len());
@@ -149,3 +243,115 @@ impl From<Vec<String>> for Books {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    impl UnorderedBooks {
+        /// Sorts every unordered book directly with a comparator derived from the rules,
+        /// avoiding building a graph per book.
+        /// Produces the same ordering as `fix_unordered_books` since the rules form a total
+        /// order over the pages of any single book.
+        fn fix_with_comparator(&self) -> OrderedBooks {
+            let must_precede = |a: &u8, b: &u8| {
+                self.rules
+                    .get(a)
+                    .map(|after| after.contains(b))
+                    .unwrap_or(false)
+            };
+
+            OrderedBooks {
+                ordered_pages: self
+                    .unordered_books
+                    .iter()
+                    .map(|book| {
+                        let mut book = book.clone();
+                        book.sort_unstable_by(|a, b| {
+                            if must_precede(a, b) {
+                                Ordering::Less
+                            } else if must_precede(b, a) {
+                                Ordering::Greater
+                            } else {
+                                Ordering::Equal
+                            }
+                        });
+                        book
+                    })
+                    .collect(),
+            }
+        }
+    }
+
+    const SAMPLE: &str = "\
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47";
+
+    fn parse(input: &str) -> Books {
+        Books::from(input.lines().map(String::from).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn comparator_sort_matches_graph_sort() {
+        let (_, unordered) = parse(SAMPLE).partition();
+        let by_comparator = unordered.fix_with_comparator().ordered_pages;
+        let by_graph = unordered.fix_unordered_books().unwrap().ordered_pages;
+
+        assert_eq!(by_comparator, by_graph);
+        assert_eq!(
+            by_comparator,
+            [
+                vec![97, 75, 47, 61, 53],
+                vec![61, 29, 13],
+                vec![97, 75, 47, 29, 13]
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_cyclic_rules() {
+        let (_, unordered) = parse("11|22\n22|33\n33|11\n44|11\n\n44,33,22,11").partition();
+        let RuleCycle(cycle) = unordered.fix_unordered_books().err().unwrap();
+        assert_eq!(cycle, [11, 22, 33, 11]);
+        assert_eq!(
+            RuleCycle(cycle).to_string(),
+            "Cycle detected in rules between pages 11 -> 22 -> 33 -> 11"
+        );
+    }
+
+    #[test]
+    fn validates_rules() {
+        assert_eq!(parse(SAMPLE).validate_rules(), Ok(()));
+        assert_eq!(
+            parse("53|13\n47|53\n53|47\n13|47\n47|13\n\n47,53").validate_rules(),
+            Err((13, 47))
+        );
+    }
+}
@@ -3,7 +3,6 @@ use aoc_utils_rust::coordinate_system::Coordinate;
 use aoc_utils_rust::day_setup::Utils;
 use aoc_utils_rust::grid::unsized_grid::UnsizedGrid;
 use aoc_utils_rust::grid::{Grid, GridMut};
-use std::collections::HashSet;
 use std::fmt::{Debug, Formatter};
 
 /// Runs the Advent of Code puzzles for [Current Day](https://adventofcode.com/202024/day/6).
@@ -36,13 +35,18 @@ struct OfficePlan {
 impl OfficePlan {
     fn count_obstruction_points(&mut self) -> usize {
         self.simulate();
-        let mut count = 0;
-        for point in self
+        let candidates = self
             .map
             .iter()
             .flat_map(|row| row.filter_map(|(coord, &e)| (e == 'X').then(|| coord)))
-            .collect::<Vec<_>>()
-        {
+            .collect::<Vec<_>>();
+        self.count_looping_obstructions(candidates)
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn count_looping_obstructions(&mut self, candidates: Vec<Coordinate<usize>>) -> usize {
+        let mut count = 0;
+        for point in candidates {
             *self.map.get_mut(&point.into()).unwrap() = '#';
             count += self.simulate() as usize;
             *self.map.get_mut(&point.into()).unwrap() = '.';
@@ -50,46 +54,106 @@ impl OfficePlan {
         count
     }
 
+    /// Each candidate obstruction is simulated on its own clone of the map.
+    #[cfg(feature = "rayon")]
+    fn count_looping_obstructions(&self, candidates: Vec<Coordinate<usize>>) -> usize {
+        use rayon::prelude::*;
+
+        candidates
+            .into_par_iter()
+            .filter(|&point| {
+                let mut office_plan = OfficePlan {
+                    map: self.map.clone(),
+                    guard_position: self.guard_position,
+                };
+                *office_plan.map.get_mut(&point.into()).unwrap() = '#';
+                office_plan.simulate()
+            })
+            .count()
+    }
+
     /// Simulate the guard's movement around the office
     /// Returns `true` if the guard is stuck in a loop, `false` otherwise
     fn simulate(&mut self) -> bool {
+        let (visited, is_loop) = self.walk(|_, _| {});
+        self.mark_visited(&visited);
+        is_loop
+    }
+
+    /// Simulate the guard's movement around the office, recording every cell the guard stands on
+    /// in order, together with the direction the guard was facing when entering it.
+    /// The simulation stops early if the guard ends up in a loop.
+    #[cfg(test)]
+    fn path(&mut self) -> Vec<(Coordinate<isize>, Direction)> {
+        let mut path: Vec<(Coordinate<isize>, Direction)> = vec![];
+        let (visited, _) = self.walk(|coord, direction| {
+            // Turning on the spot isn't a step
+            if path.last().map(|&(last, _)| last) != Some(coord) {
+                path.push((coord, direction));
+            }
+        });
+        self.mark_visited(&visited);
+        path
+    }
+
+    /// Simulate the guard's movement around the office, recording for every cell each direction
+    /// the guard faced while standing on it.
+    /// The simulation stops once the guard leaves the map or repeats a direction on a cell (a loop).
+    #[cfg(test)]
+    fn visited_directions(&self) -> UnsizedGrid<DirectionSet> {
+        self.walk(|_, _| {}).0
+    }
+
+    /// Walks the guard from its starting position until it leaves the map or repeats a direction
+    /// on a cell, calling `on_step` with every cell the guard stands on and each new direction it
+    /// faces there.
+    /// Returns the directions faced on each cell and `true` if the guard is stuck in a loop.
+    fn walk(
+        &self,
+        mut on_step: impl FnMut(Coordinate<isize>, Direction),
+    ) -> (UnsizedGrid<DirectionSet>, bool) {
+        let mut visited = UnsizedGrid::new(
+            self.map.num_rows(),
+            self.map.num_cols(),
+            DirectionSet::default(),
+        );
         let mut guard = self.guard_position;
         let mut direction = Direction::direction_list().into_iter().cycle().peekable();
-        let mut cycle_checker = HashSet::with_capacity(self.map.num_cols() * self.map.num_rows());
 
         loop {
             let curr_direction = *direction.peek().unwrap();
 
-            if !cycle_checker.insert((guard, curr_direction)) {
+            if !visited.get_mut(&guard).unwrap().insert(curr_direction) {
                 // The guard has hit a loop
-                return true;
+                return (visited, true);
             }
+            on_step(guard, curr_direction);
 
             let peek_next = guard + curr_direction;
 
-            if let Some(cell) = self.map.get_mut(&peek_next) {
-                match cell {
-                    '#' => {
-                        // The guard has hit a wall
-                        let _ = direction.next();
-                    }
-                    _ => {
-                        // NoObstacles or walking on a path traversed before
-                        // Mark the cell the guard is standing on as visited
-                        // to prepare to move to the next cell
-                        *self.map.get_mut(&guard).unwrap() = 'X';
-                        guard = peek_next;
-                    }
+            match self.map.get(&peek_next) {
+                Some(&'#') => {
+                    // The guard has hit a wall
+                    let _ = direction.next();
                 }
-            } else {
-                // Mark the final cell the guard visited before leaving the map
-                *self.map.get_mut(&guard).unwrap() = 'X';
+                // NoObstacles or walking on a path traversed before
+                Some(_) => guard = peek_next,
                 // The guard has left the perimeter
-                return false;
+                None => return (visited, false),
             }
         }
     }
 
+    /// Marks every cell the guard stood on with an `X`
+    fn mark_visited(&mut self, visited: &UnsizedGrid<DirectionSet>) {
+        for (coord, _) in visited
+            .iter()
+            .flat_map(|row| row.filter(|(_, directions)| !directions.is_empty()))
+        {
+            *self.map.get_mut(&coord.into()).unwrap() = 'X';
+        }
+    }
+
     fn count_visited(&self) -> usize {
         self.map
             .iter()
@@ -98,6 +162,34 @@ impl OfficePlan {
     }
 }
 
+/// The set of cardinal directions a guard has faced on a cell, stored as a bitmask
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct DirectionSet(u8);
+
+impl DirectionSet {
+    fn bit(direction: Direction) -> u8 {
+        match direction {
+            Direction::North => 1 << 0,
+            Direction::East => 1 << 1,
+            Direction::South => 1 << 2,
+            Direction::West => 1 << 3,
+            Direction::Current => unreachable!("The guard always faces a direction"),
+        }
+    }
+
+    /// Adds the direction to the set, returning `false` if it was already present
+    fn insert(&mut self, direction: Direction) -> bool {
+        let bit = Self::bit(direction);
+        let is_new = self.0 & bit == 0;
+        self.0 |= bit;
+        is_new
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
 impl Debug for OfficePlan {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Guard: {:?}", self.guard_position)?;
@@ -132,3 +224,115 @@ impl From<Vec<String>> for OfficePlan {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// The guard walks up column 1, turns right, then down and back left across its own path
+    fn crossing_plan() -> OfficePlan {
+        OfficePlan::from(
+            [".#...", "....#", ".....", ".^.#."]
+                .map(String::from)
+                .to_vec(),
+        )
+    }
+
+    fn direction_set(directions: &[Direction]) -> DirectionSet {
+        let mut set = DirectionSet::default();
+        directions.iter().for_each(|&direction| {
+            set.insert(direction);
+        });
+        set
+    }
+
+    fn sample_plan() -> OfficePlan {
+        OfficePlan::from(
+            [
+                "....#.....",
+                ".........#",
+                "..........",
+                "..#.......",
+                ".......#..",
+                "..........",
+                ".#..^.....",
+                "........#.",
+                "#.........",
+                "......#...",
+            ]
+            .map(String::from)
+            .to_vec(),
+        )
+    }
+
+    #[test]
+    fn counts_obstruction_points_on_sample() {
+        assert_eq!(sample_plan().count_obstruction_points(), 6);
+    }
+
+    /// Tries an obstruction on every free cell, one fresh simulation at a time
+    #[cfg(feature = "rayon")]
+    fn count_obstruction_points_by_brute_force(plan: &OfficePlan) -> usize {
+        plan.map
+            .iter()
+            .flat_map(|row| row.filter(|&(_, &e)| e == '.').map(|(coord, _)| coord))
+            .filter(|&point| {
+                let mut office_plan = OfficePlan {
+                    map: plan.map.clone(),
+                    guard_position: plan.guard_position,
+                };
+                *office_plan.map.get_mut(&point.into()).unwrap() = '#';
+                office_plan.simulate()
+            })
+            .count()
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_obstruction_count_matches_sequential() {
+        assert_eq!(
+            sample_plan().count_obstruction_points(),
+            count_obstruction_points_by_brute_force(&sample_plan())
+        );
+    }
+
+    #[test]
+    fn path_follows_the_guard() {
+        let mut plan = crossing_plan();
+        let path = plan.path();
+        assert_eq!(
+            path[..5],
+            [
+                (Coordinate::new(3, 1), Direction::North),
+                (Coordinate::new(2, 1), Direction::North),
+                (Coordinate::new(1, 1), Direction::North),
+                (Coordinate::new(1, 2), Direction::East),
+                (Coordinate::new(1, 3), Direction::East),
+            ]
+        );
+        assert_eq!(path.last(), Some(&(Coordinate::new(2, 0), Direction::West)));
+
+        let distinct = path.iter().map(|&(coord, _)| coord).collect::<HashSet<_>>();
+        assert_eq!(distinct.len(), 8);
+        assert_eq!(distinct.len(), plan.count_visited());
+    }
+
+    #[test]
+    fn records_each_direction_through_a_cell() {
+        let visited = crossing_plan().visited_directions();
+        assert_eq!(
+            visited.get(&Coordinate::new(2, 1)),
+            Some(&direction_set(&[Direction::North, Direction::West]))
+        );
+        // Turning on a cell records the direction it turned to
+        assert_eq!(
+            visited.get(&Coordinate::new(1, 1)),
+            Some(&direction_set(&[Direction::North, Direction::East]))
+        );
+        assert_eq!(
+            visited.get(&Coordinate::new(0, 0)),
+            Some(&DirectionSet::default())
+        );
+    }
+}
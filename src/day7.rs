@@ -27,6 +27,7 @@ struct OperationSequence {
     operations: Vec<(u64, Vec<u32>)>, // (Target: u32, Sources: Vec<u32>)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Operation {
     Add,
     Multiply,
@@ -39,20 +40,34 @@ impl Operation {
         match self {
             Self::Add => a + b,
             Self::Multiply => a * b,
-            Self::Concat => {
-                // Similar to format!("{}{}", a, b).parse().unwrap() but 2x slower
-                let mut a = a;
-                let mut b_temp = b;
-                while b_temp != 0 {
-                    a *= 10;
-                    b_temp /= 10;
-                }
-                a + b
-            }
+            Self::Concat => match concat_numbers(a, b) {
+                Some(concatenated) => concatenated,
+                None => panic!("Concatenation overflowed"),
+            },
         }
     }
 }
 
+/// Appends the digits of `b` to `a`, e.g. `concat_numbers(12, 345) == Some(12345)`.
+/// Returns `None` if the result doesn't fit in a `u64`.
+const fn concat_numbers(a: u64, b: u64) -> Option<u64> {
+    // Same result as format!("{}{}", a, b).parse().ok(), which is 2x slower than this
+    let mut a = a;
+    let mut b_temp = b;
+    // Shift at least once so that a `b` of 0 still adds a digit
+    loop {
+        a = match a.checked_mul(10) {
+            Some(shifted) => shifted,
+            None => return None,
+        };
+        b_temp /= 10;
+        if b_temp == 0 {
+            break;
+        }
+    }
+    a.checked_add(b)
+}
+
 impl OperationSequence {
     fn sum_valid_equations(&self, operations: &[Operation]) -> u64 {
         self.operations
@@ -86,6 +101,88 @@ impl OperationSequence {
 
         check(sources[0] as u64, &sources[1..], target, operations)
     }
+
+    /// Finds one sequence of operators which, applied left to right between the sources,
+    /// evaluates to the target.
+    #[cfg(test)]
+    fn find_operators(
+        sources: &[u32],
+        target: u64,
+        operations: &[Operation],
+    ) -> Option<Vec<Operation>> {
+        fn search(
+            acc: u64,
+            source: &[u32],
+            target: u64,
+            operations: &[Operation],
+            used: &mut Vec<Operation>,
+        ) -> bool {
+            if source.is_empty() {
+                return target == acc;
+            }
+
+            if acc > target {
+                return false;
+            }
+
+            for op in operations {
+                used.push(*op);
+                if search(
+                    op.apply(acc, source[0] as u64),
+                    &source[1..],
+                    target,
+                    operations,
+                    used,
+                ) {
+                    return true;
+                }
+                used.pop();
+            }
+
+            false
+        }
+
+        let mut used = Vec::with_capacity(sources.len() - 1);
+        search(
+            sources[0] as u64,
+            &sources[1..],
+            target,
+            operations,
+            &mut used,
+        )
+        .then_some(used)
+    }
+
+    /// Iterative counterpart of `is_possible` that keeps its partial accumulators on an
+    /// explicit stack, so very long source lists can't overflow the call stack.
+    #[cfg(test)]
+    fn is_possible_iter(sources: &[u32], target: u64, operations: &[Operation]) -> bool {
+        // (acc, index of the next source to apply)
+        let mut stack = vec![(sources[0] as u64, 1)];
+
+        while let Some((acc, idx)) = stack.pop() {
+            // Base case: If we've considered all sources, check if we hit the target
+            if idx == sources.len() {
+                if acc == target {
+                    return true;
+                }
+                continue;
+            }
+
+            // Early exit: If the current total already exceeds the target, no need to proceed
+            if acc > target {
+                continue;
+            }
+
+            stack.extend(
+                operations
+                    .iter()
+                    .map(|op| (op.apply(acc, sources[idx] as u64), idx + 1)),
+            );
+        }
+
+        false
+    }
 }
 
 impl fmt::Debug for OperationSequence {
@@ -116,3 +213,92 @@ impl From<Vec<String>> for OperationSequence {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20";
+
+    fn sample() -> OperationSequence {
+        OperationSequence::from(SAMPLE.lines().map(String::from).collect::<Vec<_>>())
+    }
+
+    const PART1_OPS: [Operation; 2] = [Operation::Add, Operation::Multiply];
+    const PART2_OPS: [Operation; 3] = [Operation::Add, Operation::Multiply, Operation::Concat];
+
+    #[test]
+    fn iterative_search_agrees_with_recursion() {
+        for (target, sources) in &sample().operations {
+            for ops in [&PART1_OPS[..], &PART2_OPS[..]] {
+                assert_eq!(
+                    OperationSequence::is_possible_iter(sources, *target, ops),
+                    OperationSequence::is_possible(sources, *target, ops),
+                    "{}: {:?}",
+                    target,
+                    sources
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn iterative_search_handles_long_source_lists() {
+        let sources = [1; 40];
+
+        assert!(OperationSequence::is_possible_iter(
+            &sources,
+            40,
+            &[Operation::Add]
+        ));
+        assert!(!OperationSequence::is_possible_iter(
+            &sources,
+            41,
+            &[Operation::Add]
+        ));
+        // Multiplying every 1 together is the first branch explored
+        assert!(OperationSequence::is_possible_iter(&sources, 1, &PART1_OPS));
+    }
+
+    #[test]
+    fn found_operators_evaluate_to_target() {
+        for (target, sources) in &sample().operations {
+            for ops in [&PART1_OPS[..], &PART2_OPS[..]] {
+                let found = OperationSequence::find_operators(sources, *target, ops);
+                assert_eq!(
+                    found.is_some(),
+                    OperationSequence::is_possible(sources, *target, ops)
+                );
+
+                if let Some(found) = found {
+                    assert_eq!(found.len(), sources.len() - 1);
+                    let value = found
+                        .iter()
+                        .zip(&sources[1..])
+                        .fold(sources[0] as u64, |acc, (op, &source)| {
+                            op.apply(acc, source as u64)
+                        });
+                    assert_eq!(value, *target);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn concatenates_numbers() {
+        assert_eq!(concat_numbers(12, 345), Some(12345));
+        assert_eq!(concat_numbers(1, 0), Some(10));
+        assert_eq!(concat_numbers(0, 7), Some(7));
+        assert_eq!(concat_numbers(u64::MAX / 10, 9), None);
+        assert_eq!(concat_numbers(u64::MAX, 1), None);
+    }
+}
@@ -37,16 +37,45 @@ struct AntennaMap {
 enum AntiNodeDistance {
     Twice,
     Unbounded,
+    /// Like `Unbounded` but only places the first N antinodes past each antenna
+    #[cfg(test)]
+    Multiples(u32),
+}
+
+impl AntiNodeDistance {
+    /// The number of antinodes placed past each antenna, or `None` for the fixed `Twice` distance
+    fn resonance_limit(self) -> Option<u32> {
+        match self {
+            AntiNodeDistance::Twice => None,
+            AntiNodeDistance::Unbounded => Some(u32::MAX),
+            #[cfg(test)]
+            AntiNodeDistance::Multiples(n) => Some(n),
+        }
+    }
 }
 
 impl AntennaMap {
     fn create_anti_node(&self, anti_node_distance: AntiNodeDistance) -> HashSet<Coordinate> {
+        let bounds = (
+            Coordinate::new(0, 0),
+            Coordinate::new(self.grid_size.0 as i32, self.grid_size.1 as i32),
+        );
+        self.anti_nodes_within(bounds, anti_node_distance)
+    }
+
+    /// Finds the antinodes that lie within `bounds`, given as the inclusive top-left and
+    /// exclusive bottom-right corners, instead of within the whole map.
+    fn anti_nodes_within(
+        &self,
+        bounds: (Coordinate, Coordinate),
+        anti_node_distance: AntiNodeDistance,
+    ) -> HashSet<Coordinate> {
         let mut anti_nodes = HashSet::new();
 
         for (_, coords) in self.antenna_map.iter() {
             for (i, c1) in coords.iter().enumerate() {
                 for c2 in coords[i + 1..].iter() {
-                    self.find_anti_node(*c1, *c2, &mut anti_nodes, anti_node_distance);
+                    Self::find_anti_node(*c1, *c2, &mut anti_nodes, anti_node_distance, bounds);
                 }
             }
         }
@@ -55,11 +84,11 @@ impl AntennaMap {
     }
 
     fn find_anti_node(
-        &self,
         c1: Coordinate,
         c2: Coordinate,
         anti_node: &mut HashSet<Coordinate>,
         anti_node_distance: AntiNodeDistance,
+        bounds: (Coordinate, Coordinate),
     ) {
         let (dx, dy) = c1.slope_relative(c2);
         if dx == 0 {
@@ -70,28 +99,36 @@ impl AntennaMap {
         let c1_temp = c1 - dx_dy_coord;
         let c2_temp = c2 + dx_dy_coord;
 
-        match anti_node_distance {
-            AntiNodeDistance::Twice => {
-                if self.in_bounds(c1_temp) {
+        match anti_node_distance.resonance_limit() {
+            None => {
+                if Self::in_bounds(c1_temp, bounds) {
                     anti_node.insert(c1_temp);
                 }
-                if self.in_bounds(c2_temp) {
+                if Self::in_bounds(c2_temp, bounds) {
                     anti_node.insert(c2_temp);
                 }
             }
-            AntiNodeDistance::Unbounded => {
-                // Add the current coordinates to the anti_node set as they form part of the anti-node
-                anti_node.insert(c1);
-                anti_node.insert(c2);
+            Some(limit) => {
+                // Add the current coordinates to the anti_node set as they form part of the anti-node,
+                // as long as any anti-nodes are being placed at all
+                if limit > 0 {
+                    anti_node.extend([c1, c2].into_iter().filter(|&c| Self::in_bounds(c, bounds)));
+                }
 
-                // Add all the coordinates in the direction of the slope to the anti_node set
+                // Add up to `limit` coordinates in the direction of the slope to the anti_node set
                 let mut c1_temp = c1_temp;
                 let mut c2_temp = c2_temp;
-                while self.in_bounds(c1_temp) {
+                for _ in 0..limit {
+                    if !Self::in_bounds(c1_temp, bounds) {
+                        break;
+                    }
                     anti_node.insert(c1_temp);
                     c1_temp -= dx_dy_coord;
                 }
-                while self.in_bounds(c2_temp) {
+                for _ in 0..limit {
+                    if !Self::in_bounds(c2_temp, bounds) {
+                        break;
+                    }
                     anti_node.insert(c2_temp);
                     c2_temp += dx_dy_coord;
                 }
@@ -99,9 +136,10 @@ impl AntennaMap {
         }
     }
 
-    fn in_bounds(&self, coord: Coordinate) -> bool {
+    fn in_bounds(coord: Coordinate, (min, max): (Coordinate, Coordinate)) -> bool {
         let (i, j) = coord.into();
-        (0..self.grid_size.0 as i32).contains(&i) && (0..self.grid_size.1 as i32).contains(&j)
+        let ((min_i, min_j), (max_i, max_j)) = (min.into(), max.into());
+        (min_i..max_i).contains(&i) && (min_j..max_j).contains(&j)
     }
 }
 
@@ -129,3 +167,85 @@ impl From<Vec<String>> for AntennaMap {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> AntennaMap {
+        AntennaMap::from(
+            [
+                "............",
+                "........0...",
+                ".....0......",
+                ".......0....",
+                "....0.......",
+                "......A.....",
+                "............",
+                "............",
+                "........A...",
+                ".........A..",
+                "............",
+                "............",
+            ]
+            .map(String::from)
+            .to_vec(),
+        )
+    }
+
+    #[test]
+    fn single_multiple_is_twice_plus_antennas() {
+        let map = sample();
+        let twice = map.create_anti_node(AntiNodeDistance::Twice);
+        let multiples = map.create_anti_node(AntiNodeDistance::Multiples(1));
+        let antennas = map
+            .antenna_map
+            .values()
+            .flatten()
+            .copied()
+            .collect::<HashSet<_>>();
+
+        assert_eq!(twice.len(), 14);
+        assert!(twice.is_subset(&multiples));
+        assert!(multiples.difference(&twice).all(|c| antennas.contains(c)));
+        assert!(map
+            .create_anti_node(AntiNodeDistance::Multiples(0))
+            .is_empty());
+    }
+
+    #[test]
+    fn multiples_are_subsets_of_unbounded() {
+        let map = sample();
+        let unbounded = map.create_anti_node(AntiNodeDistance::Unbounded);
+        assert_eq!(unbounded.len(), 34);
+
+        let mut prev = HashSet::new();
+        for n in 1..=12 {
+            let multiples = map.create_anti_node(AntiNodeDistance::Multiples(n));
+            assert!(prev.is_subset(&multiples));
+            assert!(multiples.is_subset(&unbounded));
+            prev = multiples;
+        }
+        // Nothing on a 12x12 grid is more than 12 steps away
+        assert_eq!(prev, unbounded);
+    }
+
+    #[test]
+    fn smaller_box_yields_fewer_anti_nodes() {
+        let map = sample();
+        let bounds = (Coordinate::new(0, 0), Coordinate::new(6, 6));
+
+        for (distance, boxed_len, full_len) in [
+            (AntiNodeDistance::Twice, 4, 14),
+            (AntiNodeDistance::Unbounded, 7, 34),
+        ] {
+            let boxed = map.anti_nodes_within(bounds, distance);
+            let full = map.create_anti_node(distance);
+
+            assert_eq!(boxed.len(), boxed_len);
+            assert_eq!(full.len(), full_len);
+            assert!(boxed.is_subset(&full));
+            assert!(boxed.iter().all(|&c| AntennaMap::in_bounds(c, bounds)));
+        }
+    }
+}
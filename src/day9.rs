@@ -1,4 +1,6 @@
 use aoc_utils_rust::day_setup::Utils;
+use std::fmt;
+use std::str::FromStr;
 
 /// Runs the Advent of Code puzzles for [Current Day](https://adventofcode.com/2024/day/9).
 ///
@@ -27,6 +29,11 @@ struct DiskMap {
 }
 
 impl DiskMap {
+    /// Number of files on the disk, files and free spaces alternate starting with a file
+    fn file_count(&self) -> usize {
+        self.maps.len().div_ceil(2)
+    }
+
     fn un_fragmented_check_sum(self) -> u64 {
         // (idx_in_disc, num_of_vals, nums)
         let mut map_num_with_indices: Vec<(usize, (u8, u32))> =
@@ -93,7 +100,7 @@ impl DiskMap {
         let mut r_ptr = self.maps.len() - 1;
 
         let mut l_num = 0;
-        let mut r_num = self.maps.len() / 2;
+        let mut r_num = self.file_count() - 1;
 
         let mut virtual_list_idx = 0;
 
@@ -141,9 +148,192 @@ impl DiskMap {
     }
 }
 
+/// Why a disk map couldn't be read
+#[derive(Debug, PartialEq, Eq)]
+enum DiskMapError {
+    /// A character in the disk map that isn't a block length
+    InvalidChar { position: usize, found: char },
+    /// The disk map has no blocks at all
+    Empty,
+}
+
+impl fmt::Display for DiskMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiskMapError::InvalidChar { position, found } => write!(
+                f,
+                "Invalid character `{}` at position {} in the disk map, expected a digit",
+                found, position
+            ),
+            DiskMapError::Empty => write!(f, "The disk map is empty"),
+        }
+    }
+}
+
+impl FromStr for DiskMap {
+    type Err = DiskMapError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let maps = line
+            .chars()
+            .enumerate()
+            .map(|(position, c)| {
+                c.to_digit(10)
+                    .map(|digit| digit as u8)
+                    .ok_or(DiskMapError::InvalidChar { position, found: c })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if maps.is_empty() {
+            return Err(DiskMapError::Empty);
+        }
+        Ok(Self { maps })
+    }
+}
+
 impl From<Vec<String>> for DiskMap {
     fn from(input: Vec<String>) -> Self {
-        let maps = input[0].chars().map(|s| s as u8 - b'0').collect::<Vec<_>>();
-        Self { maps }
+        input[0].parse().unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "2333133121414131402";
+
+    /// Block by block simulations of the compaction to check the checksums against
+    impl DiskMap {
+        /// Expands the dense disk map into its explicit block layout,
+        /// where `None` is a free block and `Some(id)` is a block of the file with that id.
+        fn disk_layout(&self) -> Vec<Option<u32>> {
+            self.maps
+                .iter()
+                .enumerate()
+                .flat_map(|(map_idx, &count)| {
+                    let block = if map_idx % 2 == 0 {
+                        Some((map_idx / 2) as u32)
+                    } else {
+                        None
+                    };
+                    std::iter::repeat(block).take(count as usize)
+                })
+                .collect()
+        }
+
+        /// Naively simulates moving file blocks one at a time from the end of the disk
+        /// into the leftmost free block, returning the compacted disk without its trailing free space.
+        ///
+        /// Used for verifying `fragmented_check_sum`.
+        fn compact_fragmented(&self) -> Vec<u32> {
+            let mut disk = self.disk_layout();
+
+            let mut l_ptr = 0;
+            let mut r_ptr = disk.len();
+            loop {
+                while l_ptr < r_ptr && disk[l_ptr].is_some() {
+                    l_ptr += 1;
+                }
+                while l_ptr < r_ptr && disk[r_ptr - 1].is_none() {
+                    r_ptr -= 1;
+                }
+                if l_ptr >= r_ptr {
+                    break;
+                }
+                disk.swap(l_ptr, r_ptr - 1);
+            }
+
+            disk.into_iter().map_while(|block| block).collect()
+        }
+
+        /// Naively simulates moving whole files, in decreasing file id order, into the leftmost
+        /// span of free blocks that can hold them, returning the resulting disk layout.
+        ///
+        /// Used for verifying `un_fragmented_check_sum`.
+        fn compact_whole_file(&self) -> Vec<Option<u32>> {
+            let mut disk = self.disk_layout();
+
+            for file_id in (0..self.file_count() as u32).rev() {
+                let Some(file_start) = disk.iter().position(|&block| block == Some(file_id)) else {
+                    continue;
+                };
+                let file_len = self.maps[file_id as usize * 2] as usize;
+
+                // Find the leftmost free span that can hold the file
+                let mut span_start = 0;
+                let mut span_len = 0;
+                for (idx, block) in disk[..file_start].iter().enumerate() {
+                    if block.is_some() {
+                        span_len = 0;
+                        continue;
+                    }
+                    if span_len == 0 {
+                        span_start = idx;
+                    }
+                    span_len += 1;
+                    if span_len == file_len {
+                        break;
+                    }
+                }
+
+                if file_len != 0 && span_len == file_len {
+                    for offset in 0..file_len {
+                        disk.swap(span_start + offset, file_start + offset);
+                    }
+                }
+            }
+
+            disk
+        }
+
+        /// Computes the checksum of an explicit disk layout as produced by
+        /// `compact_fragmented` or `compact_whole_file`.
+        fn layout_check_sum<I>(layout: I) -> u64
+        where
+            I: IntoIterator<Item = Option<u32>>,
+        {
+            layout
+                .into_iter()
+                .enumerate()
+                .filter_map(|(idx, block)| block.map(|file_id| idx as u64 * file_id as u64))
+                .sum()
+        }
+    }
+
+    #[test]
+    fn checksums_match_simulated_layouts() {
+        let disk_map = DiskMap::from(vec![SAMPLE.to_string()]);
+        let fragmented =
+            DiskMap::layout_check_sum(disk_map.compact_fragmented().into_iter().map(Some));
+        let whole_file = DiskMap::layout_check_sum(disk_map.compact_whole_file());
+
+        assert_eq!(fragmented, 1928);
+        assert_eq!(whole_file, 2858);
+        assert_eq!(
+            fragmented,
+            DiskMap::from(vec![SAMPLE.to_string()]).fragmented_check_sum() as u64
+        );
+        assert_eq!(
+            whole_file,
+            DiskMap::from(vec![SAMPLE.to_string()]).un_fragmented_check_sum()
+        );
+    }
+
+    #[test]
+    fn rejects_non_digit_disk_map() {
+        assert_eq!(
+            "23x3".parse::<DiskMap>().unwrap_err(),
+            DiskMapError::InvalidChar {
+                position: 2,
+                found: 'x'
+            }
+        );
+        assert!(SAMPLE.parse::<DiskMap>().is_ok());
+    }
+
+    #[test]
+    fn rejects_empty_disk_map() {
+        assert_eq!("".parse::<DiskMap>().unwrap_err(), DiskMapError::Empty);
+        assert_eq!(DiskMapError::Empty.to_string(), "The disk map is empty");
     }
 }